@@ -0,0 +1,86 @@
+//! Generate the byte frequency table of the rare byte prefilter
+//!
+//! Counts every byte of the executable sections of the x86-64 ELF files given on the command line,
+//! directories are searched one level deep. Prints the frequency rank of every byte value,
+//! in the layout of `BYTE_FREQUENCY` in `src/backends/prefilter.rs`.
+//!
+//! ```sh
+//! cargo run --release --example byte_frequency -- /usr/bin /usr/lib/x86_64-linux-gnu
+//! ```
+
+use lightningscanner::elf::Elf;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::{env, fs};
+
+const EM_X86_64: u16 = 62;
+
+fn main() {
+    let mut paths = Vec::new();
+    for arg in env::args_os().skip(1) {
+        let path = PathBuf::from(arg);
+
+        match fs::read_dir(&path) {
+            Ok(entries) => paths.extend(entries.flatten().map(|entry| entry.path())),
+            Err(_) => paths.push(path),
+        }
+    }
+
+    // symlinks such as `libfoo.so -> libfoo.so.1` would otherwise be counted twice
+    let mut seen = HashSet::new();
+    let mut counts = [0u64; 256];
+    let mut files = 0;
+
+    for path in paths {
+        let Ok(path) = fs::canonicalize(&path) else {
+            continue;
+        };
+        if !path.is_file() || !seen.insert(path.clone()) {
+            continue;
+        }
+
+        let Ok(data) = fs::read(&path) else {
+            continue;
+        };
+        let Ok(elf) = Elf::parse(&data) else {
+            continue;
+        };
+        if elf.machine() != EM_X86_64 {
+            continue;
+        }
+
+        for section in elf
+            .sections()
+            .iter()
+            .filter(|section| section.is_executable())
+        {
+            for byte in elf.section_data(section) {
+                counts[*byte as usize] += 1;
+            }
+        }
+
+        files += 1;
+    }
+
+    let total = counts.iter().sum::<u64>();
+    eprintln!("{total} bytes of code in {files} files");
+
+    // rank 0 is the least common byte, ties are ordered by byte value
+    let mut order = (0..256).collect::<Vec<_>>();
+    order.sort_by_key(|byte| counts[*byte]);
+
+    let mut ranks = [0u8; 256];
+    for (rank, byte) in order.into_iter().enumerate() {
+        ranks[byte] = rank as u8;
+    }
+
+    for (row, ranks) in ranks.chunks(16).enumerate() {
+        let ranks = ranks
+            .iter()
+            .map(|rank| format!("{rank:3}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        println!("    {ranks}, // {row:X}_");
+    }
+}
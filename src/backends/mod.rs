@@ -2,13 +2,48 @@
 
 use crate::pattern::Pattern;
 use crate::{ScanMode, ScanResult};
+use std::ops::ControlFlow;
 
 #[cfg(target_arch = "x86_64")]
 mod avx2;
+pub mod prefilter;
 mod scalar;
 #[cfg(target_arch = "x86_64")]
 mod sse42;
 
+/// Set of byte values, with nibble lookup tables for SIMD membership tests
+///
+/// Every byte is assigned to one of 8 buckets by its high nibble, a byte is a candidate member
+/// if the lookup of its low nibble and the lookup of its high nibble share a bucket.
+#[derive(Clone)]
+pub struct ByteSet {
+    bytes: [bool; 256],
+    low_nibbles: [u8; 16],
+    high_nibbles: [u8; 16],
+}
+
+impl ByteSet {
+    pub fn new() -> Self {
+        ByteSet {
+            bytes: [false; 256],
+            low_nibbles: [0; 16],
+            high_nibbles: [0; 16],
+        }
+    }
+
+    pub fn insert(&mut self, byte: u8) {
+        let bucket = 1 << ((byte >> 4) & 7);
+
+        self.bytes[byte as usize] = true;
+        self.low_nibbles[(byte & 0xf) as usize] |= bucket;
+        self.high_nibbles[(byte >> 4) as usize] |= bucket;
+    }
+
+    pub fn contains(&self, byte: u8) -> bool {
+        self.bytes[byte as usize]
+    }
+}
+
 /// Find the first occurrence of a pattern in the binary
 ///
 /// # Safety
@@ -21,24 +56,78 @@ pub unsafe fn find(
     binary: *const u8,
     binary_size: usize,
 ) -> ScanResult {
+    // the SIMD backends need at least one unit of pattern data
+    if pattern.unpadded_size == 0 {
+        return ScanResult { addr: binary };
+    }
+
+    match scan_mode(preferred_scan_mode) {
+        #[cfg(target_arch = "x86_64")]
+        ScanMode::Avx2 => {
+            // SAFETY: safe to call as long as the safety conditions were met for this function
+            unsafe { avx2::find(pattern, binary, binary_size) }
+        }
+        #[cfg(target_arch = "x86_64")]
+        ScanMode::Sse42 => {
+            // SAFETY: safe to call as long as the safety conditions were met for this function
+            unsafe { sse42::find(pattern, binary, binary_size) }
+        }
+        _ => {
+            // SAFETY: safe to call as long as the safety conditions were met for this function
+            unsafe { scalar::find(pattern, binary, binary_size) }
+        }
+    }
+}
+
+/// Visit the offset of every byte in the binary that is a member of the set, in ascending order
+///
+/// The set is only read once, so the visitor stops the scan to continue with a changed set.
+/// Returns the offset at which the visitor stopped the scan.
+///
+/// # Safety
+///
+/// * `binary` - is a valid pointer
+/// * `binary_size` - corresponds to a valid size of `binary`
+pub unsafe fn find_bytes(
+    set: &ByteSet,
+    scan_mode: ScanMode,
+    binary: *const u8,
+    binary_size: usize,
+    visit: impl FnMut(usize) -> ControlFlow<()>,
+) -> Option<usize> {
+    match scan_mode {
+        #[cfg(target_arch = "x86_64")]
+        ScanMode::Avx2 => {
+            // SAFETY: safe to call as long as the safety conditions were met for this function
+            unsafe { avx2::find_bytes(set, binary, binary_size, visit) }
+        }
+        #[cfg(target_arch = "x86_64")]
+        ScanMode::Sse42 => {
+            // SAFETY: safe to call as long as the safety conditions were met for this function
+            unsafe { sse42::find_bytes(set, binary, binary_size, visit) }
+        }
+        _ => {
+            // SAFETY: safe to call as long as the safety conditions were met for this function
+            unsafe { scalar::find_bytes(set, binary, binary_size, visit) }
+        }
+    }
+}
+
+/// Choose the scan mode to use
+///
+/// If the preferred mode is not available, chooses the fastest out of the available ones.
+pub fn scan_mode(preferred_scan_mode: Option<ScanMode>) -> ScanMode {
     #[cfg(target_arch = "x86_64")]
     {
         let avx2 = is_x86_feature_detected!("avx2");
         let sse42 = is_x86_feature_detected!("sse4.2");
 
         match (preferred_scan_mode, avx2, sse42) {
-            (Some(ScanMode::Avx2) | None, true, _) => {
-                // SAFETY: safe to call as long as the safety conditions were met for this function
-                return unsafe { avx2::find(pattern, binary, binary_size) };
-            }
-            (Some(ScanMode::Sse42), _, true) | (None, false, true) => {
-                // SAFETY: safe to call as long as the safety conditions were met for this function
-                return unsafe { sse42::find(pattern, binary, binary_size) };
-            }
+            (Some(ScanMode::Avx2) | None, true, _) => return ScanMode::Avx2,
+            (Some(ScanMode::Sse42), _, true) | (None, false, true) => return ScanMode::Sse42,
             _ => {}
         }
     }
 
-    // SAFETY: safe to call as long as the safety conditions were met for this function
-    unsafe { scalar::find(pattern, binary, binary_size) }
+    ScanMode::Scalar
 }
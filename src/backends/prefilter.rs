@@ -0,0 +1,79 @@
+//! Rare byte prefilter
+
+/// Frequency rank of every byte value in x86-64 machine code, 255 being the most common
+///
+/// Counted over the executable sections of the 1092 x86-64 ELF files in `/usr/bin`, `/usr/sbin`,
+/// `/usr/libexec` and `/usr/lib/x86_64-linux-gnu` of a Debian 12 install, 555 MB of code in total.
+/// Regenerate with `cargo run --release --example byte_frequency -- <directories>`.
+#[rustfmt::skip]
+const BYTE_FREQUENCY: [u8; 256] = [
+    255, 247, 231, 217, 230, 225, 182, 193, 239, 151, 137, 123, 177, 179,  82, 249, // 0_
+    235, 199,  86,  98, 149, 147,  83,  75, 220,  60,  52,  65, 104, 108,  48, 227, // 1_
+    222,  97,  37,  35, 251, 163,  21,  33, 216, 172,  27,  80, 103,  61, 143,  41, // 2_
+    205, 228,  36,  54,  96, 145,  22,  45, 200, 219,  53, 173, 129, 185,  34,  73, // 3_
+    229, 241, 113, 160, 244, 215, 109, 131, 254, 238,  91,  87, 248, 213,  67,  71, // 4_
+    208,  64,  55, 165, 207, 197, 114, 125, 175,  46,  44, 162, 209, 206, 115, 106, // 5_
+    171,  62, 157,  92, 155,  57, 236,  42, 164,  49,  28,  51, 146,  79,  59, 148, // 6_
+    192,  30, 124, 121, 233, 218, 100,  94, 169,  43,  26,  89, 201, 126, 119, 136, // 7_
+    212, 188,  58, 243, 240, 237,  93,  84, 178, 253,  32, 250, 181, 245,  38,  25, // 8_
+    210,  15,  23,  20, 176,  50,  10,  13, 117,  18,   3,  17, 130,  12,   1,   5, // 9_
+    122,  63,   8,  19,  40,   6,   4,   2, 120,  11,  24,  14,  68,   7,   0,  31, // A_
+    127,  39,   9,  16, 118,  29, 158, 101, 186, 132, 150,  95, 167,  77, 156, 144, // B_
+    234, 223, 170, 221, 211, 195, 198, 224, 190, 187, 102,  78, 242,  56,  81,  72, // C_
+    189, 142, 180, 111,  66,  70,  90,  74, 174, 116,  88, 154,  47,  76, 105, 183, // D_
+    194, 133, 128,  85, 107, 139, 110, 140, 246, 226, 112, 214, 168, 135, 141, 191, // E_
+    196,  99, 134, 153,  69, 138, 202, 184, 203, 159, 161, 152, 166, 204, 232, 252, // F_
+];
+
+/// The two rarest fully masked bytes of a pattern
+///
+/// Every match contains both bytes at their offsets, so backends search for positions
+/// where both bytes occur before verifying the whole pattern.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct RareBytes {
+    /// Offsets of the bytes from the start of the pattern, the rarest byte comes first
+    pub offsets: [usize; 2],
+    pub bytes: [u8; 2],
+}
+
+impl RareBytes {
+    /// Pick the rarest bytes of an unpadded pattern
+    ///
+    /// Returns `None` if the pattern has no fully masked byte,
+    /// a pattern with a single fully masked byte uses it for both.
+    pub fn new(data: &[u8], mask: &[u8]) -> Option<Self> {
+        let frequency = |offset: usize| BYTE_FREQUENCY[data[offset] as usize];
+        let rarer = |offset: usize, other: Option<usize>| match other {
+            Some(other) => frequency(offset) < frequency(other),
+            None => true,
+        };
+
+        let mut first = None;
+        let mut second = None;
+
+        for offset in (0..data.len()).filter(|&offset| mask[offset] == 0xff) {
+            if rarer(offset, first) {
+                second = first;
+                first = Some(offset);
+            } else if rarer(offset, second) {
+                second = Some(offset);
+            }
+        }
+
+        let first = first?;
+        let second = second.unwrap_or(first);
+
+        Some(RareBytes {
+            offsets: [first, second],
+            bytes: [data[first], data[second]],
+        })
+    }
+}
+
+/// Check if the prefilter produced too many candidates to be faster than comparing every window
+///
+/// Allows one candidate per 8 scanned bytes, and at least 512 candidates so short scans
+/// keep using the prefilter.
+pub fn is_ineffective(candidate_count: usize, scanned_size: usize) -> bool {
+    candidate_count * 8 > scanned_size.max(4096)
+}
@@ -1,7 +1,9 @@
 //! Scalar pattern scanning backend
 
+use crate::backends::ByteSet;
 use crate::pattern::Pattern;
 use crate::ScanResult;
+use std::ops::ControlFlow;
 use std::ptr;
 
 /// Find the first occurrence of a pattern in the binary
@@ -13,29 +15,25 @@ use std::ptr;
 ///
 /// * `binary_size` - corresponds to a valid size of `binary`
 pub unsafe fn find(pattern: &Pattern, binary: *const u8, binary_size: usize) -> ScanResult {
-    // SAFETY: safe to call as the pointer will be exactly one byte past the end of the binary
-    let binary_end = unsafe { binary.add(binary_size) };
+    let Some(last_offset) = binary_size.checked_sub(pattern.unpadded_size) else {
+        return ScanResult { addr: ptr::null() };
+    };
 
-    for binary_offset in 0..binary_size {
+    for binary_offset in 0..=last_offset {
         let mut found = true;
 
-        for pattern_offset in 0..pattern.data.len() {
-            if pattern.mask[pattern_offset] == 0x00 {
+        for pattern_offset in 0..pattern.unpadded_size {
+            let mask = pattern.mask[pattern_offset];
+            if mask == 0x00 {
                 continue;
             }
 
-            // SAFETY: safe to call as further behavior doesn't rely on overflows
-            // further reads from this address are safe because of the min call
-            // ensuring the pointer is always in bounds
-            let checked_addr = unsafe {
-                binary
-                    .add(binary_offset)
-                    .add(pattern_offset)
-                    .min(binary_end)
-            };
-
-            // SAFETY: checked addr is always in binary bounds
-            if unsafe { checked_addr.read_volatile() } != pattern.data[pattern_offset] {
+            // SAFETY: `binary_offset` never exceeds `binary_size - unpadded_size`,
+            // so the address is always in binary bounds
+            let addr = unsafe { binary.add(binary_offset + pattern_offset) };
+
+            // SAFETY: addr is always in binary bounds
+            if unsafe { addr.read_volatile() } & mask != pattern.data[pattern_offset] {
                 found = false;
                 break;
             }
@@ -49,3 +47,26 @@ pub unsafe fn find(pattern: &Pattern, binary: *const u8, binary_size: usize) ->
     }
     ScanResult { addr: ptr::null() }
 }
+
+/// Visit the offset of every byte in the binary that is a member of the set
+/// using scalar instructions
+///
+/// Returns the offset at which the visitor stopped the scan.
+///
+/// # Safety
+///
+/// * `binary` - is a valid pointer
+///
+/// * `binary_size` - corresponds to a valid size of `binary`
+pub unsafe fn find_bytes(
+    set: &ByteSet,
+    binary: *const u8,
+    binary_size: usize,
+    mut visit: impl FnMut(usize) -> ControlFlow<()>,
+) -> Option<usize> {
+    (0..binary_size).find(|&offset| {
+        // SAFETY: offset never exceeds binary_size, so the read is in binary bounds
+        let byte = unsafe { binary.add(offset).read() };
+        set.contains(byte) && visit(offset).is_break()
+    })
+}
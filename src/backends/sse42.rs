@@ -1,16 +1,23 @@
 //! SSE4.2 pattern scanning backend
 //!
+use crate::backends::{prefilter, scalar, ByteSet};
 use crate::pattern::Pattern;
 use crate::ScanResult;
 use std::arch::x86_64::{
-    _mm_blendv_epi8, _mm_cmpeq_epi8, _mm_load_si128, _mm_loadu_si128, _mm_movemask_epi8,
-    _mm_set1_epi8,
+    __m128i, _mm_and_si128, _mm_cmpeq_epi8, _mm_load_si128, _mm_loadu_si128, _mm_movemask_epi8,
+    _mm_set1_epi8, _mm_setzero_si128, _mm_shuffle_epi8, _mm_srli_epi16,
 };
+use std::ops::ControlFlow;
 use std::ptr;
 
 /// Find the first occurrence of a pattern in the binary
 /// using SSE4.2 instructions
 ///
+/// Positions containing the two rarest bytes of the pattern are found first,
+/// the whole pattern is only compared at those candidate positions. Patterns without
+/// fully masked bytes, and patterns whose rare bytes turn out to be common in the binary,
+/// are compared at every position instead.
+///
 /// # Safety
 ///
 /// * `binary` - is a valid pointer
@@ -22,42 +29,263 @@ use std::ptr;
 pub unsafe fn find(pattern_data: &Pattern, binary: *const u8, binary_size: usize) -> ScanResult {
     const UNIT_SIZE: usize = 16;
 
-    let mut processed_size = 0;
+    let Some(last_offset) = binary_size.checked_sub(pattern_data.unpadded_size) else {
+        return ScanResult { addr: ptr::null() };
+    };
 
-    // SAFETY: this function is only called if the CPU supports SSE4.2
+    let mut start = 0;
+
+    if let Some(rare_bytes) = pattern_data.rare_bytes {
+        let [first_offset, second_offset] = rare_bytes.offsets;
+        let max_offset = first_offset.max(second_offset);
+
+        // SAFETY: this function is only called if the CPU supports SSE4.2,
+        // both loads end at most at `start + max_offset + UNIT_SIZE`, which is in binary bounds
+        unsafe {
+            let mut candidate_count = 0;
+            let first_byte = _mm_set1_epi8(rare_bytes.bytes[0] as i8);
+            let second_byte = _mm_set1_epi8(rare_bytes.bytes[1] as i8);
+
+            while start + max_offset + UNIT_SIZE <= binary_size {
+                let first = _mm_loadu_si128(binary.add(start + first_offset) as *const _);
+                let second = _mm_loadu_si128(binary.add(start + second_offset) as *const _);
+
+                let eq = _mm_and_si128(
+                    _mm_cmpeq_epi8(first, first_byte),
+                    _mm_cmpeq_epi8(second, second_byte),
+                );
+                let mut candidates = _mm_movemask_epi8(eq) as u32;
+
+                while candidates != 0 {
+                    candidate_count += 1;
+
+                    let candidate = start + candidates.trailing_zeros() as usize;
+                    if candidate <= last_offset
+                        && matches_at(pattern_data, binary, binary_size, candidate)
+                    {
+                        let addr = binary.add(candidate);
+                        return ScanResult { addr };
+                    }
+
+                    candidates &= candidates - 1;
+                }
+
+                start += UNIT_SIZE;
+
+                if prefilter::is_ineffective(candidate_count, start) {
+                    break;
+                }
+            }
+        }
+    }
+
+    // SAFETY: safe to call as long as the safety conditions were met for this function
+    unsafe { find_window(pattern_data, binary, binary_size, start, last_offset) }
+}
+
+/// Find the first offset from `start` to `last_offset` at which the whole pattern matches
+///
+/// The first unit of the pattern is compared at every offset, the remaining units only
+/// if it matches. Windows that would extend past the end of the binary are compared
+/// one unit at a time with zero padded copies of the binary tail.
+///
+/// # Safety
+///
+/// * `binary` - is a valid pointer
+///
+/// * `binary_size` - corresponds to a valid size of `binary`
+///
+/// * `last_offset` - equals `binary_size - unpadded_size`
+///
+/// * Currently running CPU supports SSE4.2
+#[target_feature(enable = "sse4.2")]
+unsafe fn find_window(
+    pattern_data: &Pattern,
+    binary: *const u8,
+    binary_size: usize,
+    start: usize,
+    last_offset: usize,
+) -> ScanResult {
+    // windows starting before this offset are loaded directly from the binary
+    let direct_end = binary_size
+        .checked_sub(pattern_data.data.len())
+        .map_or(0, |offset| (offset + 1).min(last_offset + 1));
+
+    let mut offset = start;
+
+    // SAFETY: this function is only called if the CPU supports SSE4.2,
+    // direct loads end at most at `direct_end - 1 + padded_size`, which is in binary bounds
     unsafe {
-        let mut pattern = _mm_load_si128(pattern_data.data.as_ptr() as *const _);
-        let mut mask = _mm_load_si128(pattern_data.mask.as_ptr() as *const _);
-        let all_zeros = _mm_set1_epi8(0x00);
+        let first_pattern = _mm_load_si128(pattern_data.data.as_ptr() as *const _);
+        let first_mask = _mm_load_si128(pattern_data.mask.as_ptr() as *const _);
 
-        let mut chunk = 0;
+        while offset < direct_end {
+            let chunk_data = _mm_loadu_si128(binary.add(offset) as *const _);
+            let eq = _mm_cmpeq_epi8(first_pattern, _mm_and_si128(chunk_data, first_mask));
 
-        while chunk < binary_size {
-            let chunk_data = _mm_loadu_si128(binary.add(chunk) as *const _);
-            let blend = _mm_blendv_epi8(all_zeros, chunk_data, mask);
-            let eq = _mm_cmpeq_epi8(pattern, blend);
+            if _mm_movemask_epi8(eq) as u32 == 0xffff
+                && matches_at(pattern_data, binary, binary_size, offset)
+            {
+                let addr = binary.add(offset);
+                return ScanResult { addr };
+            }
+
+            offset += 1;
+        }
+    }
+
+    for offset in offset..=last_offset {
+        // SAFETY: `offset` never exceeds `binary_size - unpadded_size`
+        if unsafe { matches_at(pattern_data, binary, binary_size, offset) } {
+            // SAFETY: `offset` is in binary bounds
+            let addr = unsafe { binary.add(offset) };
+            return ScanResult { addr };
+        }
+    }
+
+    ScanResult { addr: ptr::null() }
+}
+
+/// Check if the pattern matches the binary at `offset`
+///
+/// # Safety
+///
+/// * `binary` - is a valid pointer
+///
+/// * `binary_size` - corresponds to a valid size of `binary`
+///
+/// * `offset` - does not exceed `binary_size - unpadded_size`
+///
+/// * Currently running CPU supports SSE4.2
+#[inline]
+#[target_feature(enable = "sse4.2")]
+unsafe fn matches_at(
+    pattern_data: &Pattern,
+    binary: *const u8,
+    binary_size: usize,
+    offset: usize,
+) -> bool {
+    const UNIT_SIZE: usize = 16;
+
+    let mut processed_size = 0;
+
+    // SAFETY: this function is only called if the CPU supports SSE4.2,
+    // pattern data and mask are padded to a multiple of the unit size
+    unsafe {
+        while processed_size < pattern_data.unpadded_size {
+            let pattern =
+                _mm_load_si128(pattern_data.data.as_ptr().add(processed_size) as *const _);
+            let mask = _mm_load_si128(pattern_data.mask.as_ptr().add(processed_size) as *const _);
+
+            let chunk_data = load_unit(binary, binary_size, offset + processed_size);
+            let eq = _mm_cmpeq_epi8(pattern, _mm_and_si128(chunk_data, mask));
 
             if _mm_movemask_epi8(eq) == 0xffff {
                 processed_size += UNIT_SIZE;
+            } else {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+/// Load 16 bytes of the binary starting at `offset`
+///
+/// Near the end of the binary the remaining bytes are copied into a zero padded buffer,
+/// so no bytes past the end of the binary are read.
+///
+/// # Safety
+///
+/// * `binary` - is a valid pointer
+///
+/// * `binary_size` - corresponds to a valid size of `binary`
+///
+/// * `offset` - is smaller than `binary_size`
+///
+/// * Currently running CPU supports SSE4.2
+#[inline]
+#[target_feature(enable = "sse4.2")]
+unsafe fn load_unit(binary: *const u8, binary_size: usize, offset: usize) -> __m128i {
+    const UNIT_SIZE: usize = 16;
+
+    // SAFETY: this function is only called if the CPU supports SSE4.2,
+    // reads never go past `binary_size` bytes
+    unsafe {
+        if offset + UNIT_SIZE <= binary_size {
+            _mm_loadu_si128(binary.add(offset) as *const _)
+        } else {
+            let mut tail = [0u8; UNIT_SIZE];
+            ptr::copy_nonoverlapping(binary.add(offset), tail.as_mut_ptr(), binary_size - offset);
+
+            _mm_loadu_si128(tail.as_ptr() as *const _)
+        }
+    }
+}
+
+/// Visit the offset of every byte in the binary that is a member of the set
+/// using SSE4.2 instructions
+///
+/// Returns the offset at which the visitor stopped the scan.
+///
+/// # Safety
+///
+/// * `binary` - is a valid pointer
+///
+/// * `binary_size` - corresponds to a valid size of `binary`
+///
+/// * Currently running CPU supports SSE4.2
+#[target_feature(enable = "sse4.2")]
+pub unsafe fn find_bytes(
+    set: &ByteSet,
+    binary: *const u8,
+    binary_size: usize,
+    mut visit: impl FnMut(usize) -> ControlFlow<()>,
+) -> Option<usize> {
+    const UNIT_SIZE: usize = 16;
 
-                if processed_size < pattern_data.unpadded_size {
-                    chunk += UNIT_SIZE - 1;
-                    pattern =
-                        _mm_load_si128(pattern_data.data.as_ptr().add(processed_size) as *const _);
-                    mask =
-                        _mm_load_si128(pattern_data.mask.as_ptr().add(processed_size) as *const _);
-                } else {
-                    let addr = binary.add(chunk).sub(processed_size).add(UNIT_SIZE);
-                    return ScanResult { addr };
+    let mut chunk = 0;
+
+    // SAFETY: this function is only called if the CPU supports SSE4.2
+    unsafe {
+        let low_table = _mm_loadu_si128(set.low_nibbles.as_ptr() as *const _);
+        let high_table = _mm_loadu_si128(set.high_nibbles.as_ptr() as *const _);
+        let nibble_mask = _mm_set1_epi8(0x0f);
+        let all_zeros = _mm_setzero_si128();
+
+        while chunk + UNIT_SIZE <= binary_size {
+            let chunk_data = _mm_loadu_si128(binary.add(chunk) as *const _);
+
+            let low = _mm_and_si128(chunk_data, nibble_mask);
+            let high = _mm_and_si128(_mm_srli_epi16(chunk_data, 4), nibble_mask);
+            let buckets = _mm_and_si128(
+                _mm_shuffle_epi8(low_table, low),
+                _mm_shuffle_epi8(high_table, high),
+            );
+
+            let empty = _mm_movemask_epi8(_mm_cmpeq_epi8(buckets, all_zeros)) as u32;
+            let mut candidates = !empty & 0xffff;
+
+            // buckets can be shared by bytes outside of the set, so every candidate is checked
+            while candidates != 0 {
+                let offset = chunk + candidates.trailing_zeros() as usize;
+                if set.contains(binary.add(offset).read()) && visit(offset).is_break() {
+                    return Some(offset);
                 }
-            } else {
-                pattern = _mm_load_si128(pattern_data.data.as_ptr() as *const _);
-                mask = _mm_load_si128(pattern_data.mask.as_ptr() as *const _);
-                processed_size = 0;
+
+                candidates &= candidates - 1;
             }
 
-            chunk += 1;
+            chunk += UNIT_SIZE;
         }
     }
-    ScanResult { addr: ptr::null() }
+
+    // SAFETY: chunk never exceeds binary_size, so the tail is in binary bounds
+    unsafe {
+        scalar::find_bytes(set, binary.add(chunk), binary_size - chunk, |offset| {
+            visit(chunk + offset)
+        })
+    }
+    .map(|offset| chunk + offset)
 }
@@ -0,0 +1,297 @@
+//! Scanning of process memory dumps, such as ELF core files and Windows minidumps
+
+use crate::elf::{Elf, ElfError};
+use crate::reader::Reader;
+use crate::Scanner;
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+const ET_CORE: u16 = 4;
+
+const MINIDUMP_SIGNATURE: &[u8; 4] = b"MDMP";
+const MEMORY_LIST_STREAM: u32 = 5;
+const MEMORY64_LIST_STREAM: u32 = 9;
+
+/// Error returned when parsing a memory dump fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum DumpError {
+    /// The core file is not a valid ELF file
+    Elf(ElfError),
+    /// The ELF file is not a core file, contains the actual object file type
+    NotCoreFile(u16),
+    /// The file is neither an ELF core file nor a minidump
+    UnknownFormat,
+    /// A header, stream or memory range lies outside of the file
+    Truncated,
+}
+
+impl fmt::Display for DumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DumpError::Elf(err) => write!(f, "invalid core file: {err}"),
+            DumpError::NotCoreFile(kind) => write!(f, "ELF file of type {kind} is not a core file"),
+            DumpError::UnknownFormat => write!(f, "unknown memory dump format"),
+            DumpError::Truncated => write!(f, "truncated memory dump"),
+        }
+    }
+}
+
+impl Error for DumpError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DumpError::Elf(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ElfError> for DumpError {
+    fn from(value: ElfError) -> Self {
+        DumpError::Elf(value)
+    }
+}
+
+/// A range of process memory stored in a dump
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryRegion {
+    address: u64,
+    file_range: Range<usize>,
+}
+
+impl MemoryRegion {
+    /// Get the virtual address of the region in the dumped process
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    /// Get the virtual address range of the region in the dumped process
+    pub fn address_range(&self) -> Range<u64> {
+        self.address..self.address.saturating_add(self.len() as u64)
+    }
+
+    /// Get the offsets of the region data in the dump file
+    pub fn file_range(&self) -> Range<usize> {
+        self.file_range.clone()
+    }
+
+    /// Get the size of the region in bytes
+    pub fn len(&self) -> usize {
+        self.file_range.len()
+    }
+
+    /// Check if the region is empty
+    pub fn is_empty(&self) -> bool {
+        self.file_range.is_empty()
+    }
+}
+
+/// Memory regions of a process dump
+///
+/// Matches are reported as virtual addresses of the dumped process, including the result offset
+/// of the pattern. Every region is scanned separately, so matches spanning multiple regions
+/// are not found even if the regions are adjacent in memory.
+///
+/// # Example
+///
+/// ```no_run
+/// use lightningscanner::dump::MemoryDump;
+/// use lightningscanner::Scanner;
+///
+/// let data = std::fs::read("crash.dmp").unwrap();
+/// let dump = MemoryDump::parse(&data).unwrap();
+///
+/// let scanner = Scanner::new("de c0 ad de ?? ?? ?? ?? 01 00 00 00");
+/// for address in dump.find_all(&scanner) {
+///     println!("found at {address:#x}");
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MemoryDump<'a> {
+    data: &'a [u8],
+    regions: Vec<MemoryRegion>,
+}
+
+impl<'a> MemoryDump<'a> {
+    /// Parse an ELF core file or a minidump, depending on the file signature
+    pub fn parse(data: &'a [u8]) -> Result<Self, DumpError> {
+        if data.starts_with(b"\x7fELF") {
+            Self::parse_core(data)
+        } else if data.starts_with(MINIDUMP_SIGNATURE) {
+            Self::parse_minidump(data)
+        } else {
+            Err(DumpError::UnknownFormat)
+        }
+    }
+
+    /// Parse an ELF core file, its memory regions are the `PT_LOAD` segments with file data
+    pub fn parse_core(data: &'a [u8]) -> Result<Self, DumpError> {
+        let elf = Elf::parse(data)?;
+        if elf.kind() != ET_CORE {
+            return Err(DumpError::NotCoreFile(elf.kind()));
+        }
+
+        let regions = elf
+            .load_segments()
+            .map(|segment| MemoryRegion {
+                address: segment.address(),
+                file_range: segment.file_range(),
+            })
+            .collect();
+
+        Ok(Self::new(data, regions))
+    }
+
+    /// Parse a minidump, its memory regions are listed by the
+    /// `Memory64ListStream` and `MemoryListStream` streams
+    pub fn parse_minidump(data: &'a [u8]) -> Result<Self, DumpError> {
+        if !data.starts_with(MINIDUMP_SIGNATURE) {
+            return Err(DumpError::UnknownFormat);
+        }
+
+        let regions = Self::minidump_regions(data).ok_or(DumpError::Truncated)?;
+        if regions
+            .iter()
+            .any(|region| region.file_range.end > data.len())
+        {
+            return Err(DumpError::Truncated);
+        }
+
+        Ok(Self::new(data, regions))
+    }
+
+    fn minidump_regions(data: &'a [u8]) -> Option<Vec<MemoryRegion>> {
+        let reader = Reader::new(data, false);
+
+        let stream_count = reader.u32(0x8)? as usize;
+        let directory = reader.u32(0xc)? as usize;
+
+        let mut regions = Vec::new();
+        for index in 0..stream_count {
+            let entry = directory.checked_add(index * 0xc)?;
+            let stream = reader.u32(entry + 0x8)? as usize;
+
+            match reader.u32(entry)? {
+                MEMORY_LIST_STREAM => {
+                    let count = reader.u32(stream)? as usize;
+
+                    for index in 0..count {
+                        let descriptor = stream.checked_add(0x4 + index * 0x10)?;
+                        let size = reader.u32(descriptor + 0x8)? as usize;
+                        let offset = reader.u32(descriptor + 0xc)? as usize;
+
+                        regions.push(MemoryRegion {
+                            address: reader.u64(descriptor)?,
+                            file_range: offset..offset.checked_add(size)?,
+                        });
+                    }
+                }
+                MEMORY64_LIST_STREAM => {
+                    let count = reader.u64(stream)? as usize;
+                    let mut offset = usize::try_from(reader.u64(stream + 0x8)?).ok()?;
+
+                    // the memory of all ranges is stored consecutively, starting at the base offset
+                    for index in 0..count {
+                        let descriptor = stream.checked_add(0x10 + index.checked_mul(0x10)?)?;
+                        let size = usize::try_from(reader.u64(descriptor + 0x8)?).ok()?;
+
+                        regions.push(MemoryRegion {
+                            address: reader.u64(descriptor)?,
+                            file_range: offset..offset.checked_add(size)?,
+                        });
+                        offset += size;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Some(regions)
+    }
+
+    fn new(data: &'a [u8], mut regions: Vec<MemoryRegion>) -> Self {
+        regions.retain(|region| !region.is_empty());
+        regions.sort_by_key(|region| region.address);
+
+        MemoryDump { data, regions }
+    }
+
+    /// Get the raw dump file data
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Get the memory regions of the dump, sorted by address
+    pub fn regions(&self) -> &[MemoryRegion] {
+        &self.regions
+    }
+
+    /// Get the data of a memory region
+    pub fn region_data(&self, region: &MemoryRegion) -> &'a [u8] {
+        &self.data[region.file_range()]
+    }
+
+    /// Read `len` bytes of dumped memory at a virtual address
+    ///
+    /// Returns `None` if the memory is not fully contained in a single region.
+    pub fn read(&self, address: u64, len: usize) -> Option<&'a [u8]> {
+        let region = self
+            .regions
+            .iter()
+            .find(|region| region.address_range().contains(&address))?;
+
+        let start = region.file_range.start + (address - region.address) as usize;
+        let end = start.checked_add(len)?;
+
+        (end <= region.file_range.end).then(|| &self.data[start..end])
+    }
+
+    /// Convert a dump file offset to the virtual address of the dumped memory
+    pub fn offset_to_address(&self, offset: usize) -> Option<u64> {
+        self.regions
+            .iter()
+            .find(|region| region.file_range.contains(&offset))
+            .map(|region| region.address + (offset - region.file_range.start) as u64)
+    }
+
+    /// Convert a virtual address of the dumped memory to a dump file offset
+    pub fn address_to_offset(&self, address: u64) -> Option<usize> {
+        self.regions
+            .iter()
+            .find(|region| region.address_range().contains(&address))
+            .map(|region| region.file_range.start + (address - region.address) as usize)
+    }
+
+    /// Find the first occurence of the pattern in the dumped memory
+    ///
+    /// Returns the virtual address of the match, including the result offset of the pattern.
+    pub fn find(&self, scanner: &Scanner) -> Option<u64> {
+        self.regions.iter().find_map(|region| {
+            let result = scanner.find_in(self.region_data(region))?;
+            Some(region.address + result.offset() as u64)
+        })
+    }
+
+    /// Find every occurence of the pattern in the dumped memory
+    ///
+    /// Returns the virtual addresses of the matches in ascending order,
+    /// including the result offset of the pattern.
+    /// Matches in overlapping regions are only returned once.
+    pub fn find_all(&self, scanner: &Scanner) -> Vec<u64> {
+        let mut addresses = self
+            .regions
+            .iter()
+            .flat_map(|region| {
+                scanner
+                    .find_all_in(self.region_data(region))
+                    .map(|result| region.address + result.offset() as u64)
+            })
+            .collect::<Vec<_>>();
+
+        // minidumps may contain overlapping or duplicated memory ranges
+        addresses.sort_unstable();
+        addresses.dedup();
+        addresses
+    }
+}
@@ -0,0 +1,439 @@
+//! ELF file parsing and section-restricted scanning
+
+use crate::reader::Reader;
+use crate::{Match, Scanner};
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+const MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+
+const SHT_NOBITS: u32 = 8;
+const SHF_WRITE: u64 = 0x1;
+const SHF_ALLOC: u64 = 0x2;
+const SHF_EXECINSTR: u64 = 0x4;
+
+const PT_LOAD: u32 = 1;
+const PF_X: u32 = 0x1;
+const PF_W: u32 = 0x2;
+const PF_R: u32 = 0x4;
+
+/// Error returned when parsing an ELF file fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ElfError {
+    /// The file does not start with the ELF magic
+    BadMagic,
+    /// The file class is neither ELF32 nor ELF64
+    UnsupportedClass(u8),
+    /// The data encoding is neither little nor big endian
+    UnsupportedEncoding(u8),
+    /// A header, section or segment lies outside of the file
+    Truncated,
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfError::BadMagic => write!(f, "missing ELF magic"),
+            ElfError::UnsupportedClass(class) => write!(f, "unsupported ELF class {class}"),
+            ElfError::UnsupportedEncoding(encoding) => {
+                write!(f, "unsupported ELF data encoding {encoding}")
+            }
+            ElfError::Truncated => write!(f, "truncated ELF file"),
+        }
+    }
+}
+
+impl Error for ElfError {}
+
+/// A section of an ELF file, as described by its section header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    name: String,
+    kind: u32,
+    flags: u64,
+    address: u64,
+    offset: u64,
+    size: u64,
+}
+
+impl Section {
+    /// Get the name of the section, such as `.text`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the section type (`sh_type`)
+    pub fn kind(&self) -> u32 {
+        self.kind
+    }
+
+    /// Get the section flags (`sh_flags`)
+    pub fn flags(&self) -> u64 {
+        self.flags
+    }
+
+    /// Get the virtual address of the section, zero if it isn't loaded into memory
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    /// Get the size of the section
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Get the file offsets of the section data, `None` for sections without data in the file
+    pub fn file_range(&self) -> Option<Range<usize>> {
+        if self.kind == SHT_NOBITS {
+            return None;
+        }
+
+        Some(self.offset as usize..self.offset.saturating_add(self.size) as usize)
+    }
+
+    /// Check if the section occupies memory during execution
+    pub fn is_alloc(&self) -> bool {
+        self.flags & SHF_ALLOC != 0
+    }
+
+    /// Check if the section is writable during execution
+    pub fn is_writable(&self) -> bool {
+        self.flags & SHF_WRITE != 0
+    }
+
+    /// Check if the section contains executable instructions
+    pub fn is_executable(&self) -> bool {
+        self.flags & SHF_EXECINSTR != 0
+    }
+}
+
+/// A segment of an ELF file, as described by its program header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    kind: u32,
+    flags: u32,
+    offset: u64,
+    file_size: u64,
+    address: u64,
+    memory_size: u64,
+}
+
+impl Segment {
+    /// Get the segment type (`p_type`)
+    pub fn kind(&self) -> u32 {
+        self.kind
+    }
+
+    /// Check if the segment is loaded into memory (`PT_LOAD`)
+    pub fn is_load(&self) -> bool {
+        self.kind == PT_LOAD
+    }
+
+    /// Get the virtual address of the segment
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    /// Get the size of the segment in memory
+    pub fn memory_size(&self) -> u64 {
+        self.memory_size
+    }
+
+    /// Get the file offsets of the segment data
+    pub fn file_range(&self) -> Range<usize> {
+        self.offset as usize..self.offset.saturating_add(self.file_size) as usize
+    }
+
+    /// Check if the segment is readable
+    pub fn is_readable(&self) -> bool {
+        self.flags & PF_R != 0
+    }
+
+    /// Check if the segment is writable
+    pub fn is_writable(&self) -> bool {
+        self.flags & PF_W != 0
+    }
+
+    /// Check if the segment is executable
+    pub fn is_executable(&self) -> bool {
+        self.flags & PF_X != 0
+    }
+}
+
+/// A parsed ELF32 or ELF64 file of either endianness
+///
+/// # Example
+///
+/// ```no_run
+/// use lightningscanner::elf::Elf;
+/// use lightningscanner::Scanner;
+///
+/// let data = std::fs::read("libfoo.so").unwrap();
+/// let elf = Elf::parse(&data).unwrap();
+///
+/// let scanner = Scanner::new("48 89 5c 24 ?? 48 89 6c");
+/// if let Some(result) = elf.find_in_section(&scanner, ".text") {
+///     println!("found at {:#x}", elf.offset_to_address(result.offset()).unwrap());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Elf<'a> {
+    data: &'a [u8],
+    is_64: bool,
+    big_endian: bool,
+    kind: u16,
+    machine: u16,
+    entry: u64,
+    sections: Vec<Section>,
+    segments: Vec<Segment>,
+}
+
+impl<'a> Elf<'a> {
+    /// Parse the headers of an ELF file
+    pub fn parse(data: &'a [u8]) -> Result<Self, ElfError> {
+        if !data.starts_with(&MAGIC) {
+            return Err(ElfError::BadMagic);
+        }
+
+        let is_64 = match data.get(4) {
+            Some(1) => false,
+            Some(2) => true,
+            Some(&class) => return Err(ElfError::UnsupportedClass(class)),
+            None => return Err(ElfError::Truncated),
+        };
+        let big_endian = match data.get(5) {
+            Some(1) => false,
+            Some(2) => true,
+            Some(&encoding) => return Err(ElfError::UnsupportedEncoding(encoding)),
+            None => return Err(ElfError::Truncated),
+        };
+
+        let elf = Self::parse_headers(data, is_64, big_endian).ok_or(ElfError::Truncated)?;
+
+        let in_bounds = |range: Range<usize>| range.start <= range.end && range.end <= data.len();
+        let sections_in_bounds = elf
+            .sections
+            .iter()
+            .filter_map(Section::file_range)
+            .all(in_bounds);
+        let segments_in_bounds = elf.load_segments().map(Segment::file_range).all(in_bounds);
+
+        if !sections_in_bounds || !segments_in_bounds {
+            return Err(ElfError::Truncated);
+        }
+
+        Ok(elf)
+    }
+
+    fn parse_headers(data: &'a [u8], is_64: bool, big_endian: bool) -> Option<Self> {
+        let reader = Reader::new(data, big_endian);
+
+        let kind = reader.u16(0x10)?;
+        let machine = reader.u16(0x12)?;
+        let entry = reader.word(0x18, is_64)?;
+
+        let (program_headers, section_headers, sizes) = if is_64 {
+            (reader.u64(0x20)?, reader.u64(0x28)?, 0x36)
+        } else {
+            (reader.u32(0x1c)? as u64, reader.u32(0x20)? as u64, 0x2a)
+        };
+        let program_header_size = reader.u16(sizes)? as usize;
+        let program_header_count = reader.u16(sizes + 2)? as usize;
+        let section_header_size = reader.u16(sizes + 4)? as usize;
+        let mut section_header_count = reader.u16(sizes + 6)? as usize;
+        let mut names_index = reader.u16(sizes + 8)? as usize;
+
+        let section_header = |index: usize| {
+            (section_headers as usize).checked_add(index.checked_mul(section_header_size)?)
+        };
+
+        // extended numbering stores large counts in the first section header
+        if section_headers != 0 && section_header_count == 0 {
+            section_header_count =
+                reader.word(section_header(0)? + if is_64 { 0x20 } else { 0x14 }, is_64)? as usize;
+        }
+        if names_index == 0xffff {
+            names_index =
+                reader.u32(section_header(0)? + if is_64 { 0x28 } else { 0x18 })? as usize;
+        }
+
+        let mut segments = Vec::new();
+        for index in 0..program_header_count {
+            let header =
+                (program_headers as usize).checked_add(index.checked_mul(program_header_size)?)?;
+
+            segments.push(if is_64 {
+                Segment {
+                    kind: reader.u32(header)?,
+                    flags: reader.u32(header + 0x4)?,
+                    offset: reader.u64(header + 0x8)?,
+                    address: reader.u64(header + 0x10)?,
+                    file_size: reader.u64(header + 0x20)?,
+                    memory_size: reader.u64(header + 0x28)?,
+                }
+            } else {
+                Segment {
+                    kind: reader.u32(header)?,
+                    offset: reader.u32(header + 0x4)? as u64,
+                    address: reader.u32(header + 0x8)? as u64,
+                    file_size: reader.u32(header + 0x10)? as u64,
+                    memory_size: reader.u32(header + 0x14)? as u64,
+                    flags: reader.u32(header + 0x18)?,
+                }
+            });
+        }
+
+        let mut sections = Vec::new();
+        let mut name_offsets = Vec::new();
+        for index in 0..section_header_count {
+            let header = section_header(index)?;
+
+            name_offsets.push(reader.u32(header)? as usize);
+            sections.push(Section {
+                name: String::new(),
+                kind: reader.u32(header + 0x4)?,
+                flags: reader.word(header + 0x8, is_64)?,
+                address: reader.word(header + if is_64 { 0x10 } else { 0xc }, is_64)?,
+                offset: reader.word(header + if is_64 { 0x18 } else { 0x10 }, is_64)?,
+                size: reader.word(header + if is_64 { 0x20 } else { 0x14 }, is_64)?,
+            });
+        }
+
+        if let Some(names) = sections
+            .get(names_index)
+            .map(|section| section.offset as usize)
+        {
+            for (section, name_offset) in sections.iter_mut().zip(name_offsets) {
+                let name = reader.c_str(names.checked_add(name_offset)?)?;
+                section.name = String::from_utf8_lossy(name).into_owned();
+            }
+        }
+
+        Some(Elf {
+            data,
+            is_64,
+            big_endian,
+            kind,
+            machine,
+            entry,
+            sections,
+            segments,
+        })
+    }
+
+    /// Get the raw file data
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Check if the file is an ELF64 file
+    pub fn is_64(&self) -> bool {
+        self.is_64
+    }
+
+    /// Check if the file is big endian
+    pub fn is_big_endian(&self) -> bool {
+        self.big_endian
+    }
+
+    /// Get the object file type (`e_type`)
+    pub fn kind(&self) -> u16 {
+        self.kind
+    }
+
+    /// Get the target architecture (`e_machine`)
+    pub fn machine(&self) -> u16 {
+        self.machine
+    }
+
+    /// Get the virtual address of the entry point
+    pub fn entry(&self) -> u64 {
+        self.entry
+    }
+
+    /// Get all sections of the file
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// Get the first section with the given name
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Get the data of a section, empty for sections without data in the file
+    pub fn section_data(&self, section: &Section) -> &'a [u8] {
+        section
+            .file_range()
+            .map_or(&[][..], |range| &self.data[range])
+    }
+
+    /// Get all segments of the file
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Get the segments loaded into memory
+    pub fn load_segments(&self) -> impl Iterator<Item = &Segment> {
+        self.segments.iter().filter(|segment| segment.is_load())
+    }
+
+    /// Convert a file offset to the virtual address it is loaded at
+    ///
+    /// Returns `None` if the offset is not part of a loaded segment.
+    pub fn offset_to_address(&self, offset: usize) -> Option<u64> {
+        self.load_segments()
+            .find(|segment| segment.file_range().contains(&offset))
+            .map(|segment| segment.address + (offset as u64 - segment.offset))
+    }
+
+    /// Convert a virtual address to the file offset it is loaded from
+    ///
+    /// Returns `None` if the address is not backed by file data of a loaded segment.
+    pub fn address_to_offset(&self, address: u64) -> Option<usize> {
+        self.load_segments()
+            .find(|segment| {
+                address >= segment.address && address - segment.address < segment.file_size
+            })
+            .map(|segment| (segment.offset + (address - segment.address)) as usize)
+    }
+
+    /// Find the first occurence of the pattern in the section with the given name
+    ///
+    /// The match offset is a file offset, see [`Elf::offset_to_address`] to get its virtual address.
+    pub fn find_in_section(&self, scanner: &Scanner, name: &str) -> Option<Match<'a>> {
+        let range = self.section(name)?.file_range()?;
+        scanner.find_in_range(self.data, range)
+    }
+
+    /// Find every occurence of the pattern in the section with the given name
+    ///
+    /// The match offsets are file offsets, see [`Elf::offset_to_address`] to get their virtual addresses.
+    pub fn find_all_in_section(&self, scanner: &Scanner, name: &str) -> Vec<Match<'a>> {
+        match self.section(name).and_then(Section::file_range) {
+            Some(range) => scanner.find_all_in_range(self.data, range).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Find the first occurence of the pattern in the executable loaded segments
+    ///
+    /// The match offset is a file offset, see [`Elf::offset_to_address`] to get its virtual address.
+    pub fn find_in_executable_segments(&self, scanner: &Scanner) -> Option<Match<'a>> {
+        self.load_segments()
+            .filter(|segment| segment.is_executable())
+            .find_map(|segment| scanner.find_in_range(self.data, segment.file_range()))
+    }
+
+    /// Find every occurence of the pattern in the executable loaded segments
+    ///
+    /// The match offsets are file offsets, see [`Elf::offset_to_address`] to get their virtual addresses.
+    pub fn find_all_in_executable_segments(&self, scanner: &Scanner) -> Vec<Match<'a>> {
+        self.load_segments()
+            .filter(|segment| segment.is_executable())
+            .flat_map(|segment| scanner.find_all_in_range(self.data, segment.file_range()))
+            .collect()
+    }
+}
@@ -0,0 +1,68 @@
+//! Scanning of memory-mapped files
+
+use crate::Scanner;
+use memmap2::Mmap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+impl Scanner {
+    /// Find the first occurence of the pattern in a file
+    ///
+    /// The file is memory-mapped read-only instead of being read into memory,
+    /// returns the file offset of the match, including the result offset of the pattern.
+    ///
+    /// The file must not be modified by another process while it is being scanned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lightningscanner::Scanner;
+    ///
+    /// let scanner = Scanner::new("48 89 5c 24 ?? 48 89 6c");
+    ///
+    /// if let Some(offset) = scanner.find_in_file("game.exe").unwrap() {
+    ///     println!("found at {offset:#x}");
+    /// }
+    /// ```
+    pub fn find_in_file(&self, path: impl AsRef<Path>) -> io::Result<Option<usize>> {
+        let map = map_file(path.as_ref())?;
+
+        Ok(self.find_in(&map).map(|result| result.offset()))
+    }
+
+    /// Find every occurence of the pattern in a file
+    ///
+    /// The file is memory-mapped read-only instead of being read into memory,
+    /// returns the file offsets of the matches, including the result offset of the pattern.
+    ///
+    /// The file must not be modified by another process while it is being scanned.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use lightningscanner::Scanner;
+    ///
+    /// let scanner = Scanner::new("e8 ?? ?? ?? ??");
+    /// let offsets = scanner.find_all_in_file("game.exe").unwrap();
+    ///
+    /// println!("{} calls", offsets.len());
+    /// ```
+    pub fn find_all_in_file(&self, path: impl AsRef<Path>) -> io::Result<Vec<usize>> {
+        let map = map_file(path.as_ref())?;
+
+        Ok(self
+            .find_all_in(&map)
+            .map(|result| result.offset())
+            .collect())
+    }
+}
+
+/// Memory-map a file read-only
+fn map_file(path: &Path) -> io::Result<Mmap> {
+    let file = File::open(path)?;
+
+    // SAFETY: the mapping is read-only and private to the scanner, modifications of the file
+    // by other processes while scanning are documented as unsupported
+    unsafe { Mmap::map(&file) }
+}
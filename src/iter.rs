@@ -0,0 +1,121 @@
+//! Iterators over every occurrence of a pattern
+
+use crate::pattern::Pattern;
+use crate::{backends, Match, ScanMode, ScanResult};
+use std::iter::FusedIterator;
+
+/// Iterator over every occurrence of a pattern in a binary
+///
+/// Created by [`Scanner::find_all`](crate::Scanner::find_all).
+pub struct ScanResults<'s> {
+    pattern: &'s Pattern,
+    preferred_scan_mode: Option<ScanMode>,
+    binary: *const u8,
+    binary_size: usize,
+    position: usize,
+}
+
+impl<'s> ScanResults<'s> {
+    /// # Safety
+    ///
+    /// * `binary` - is a valid pointer for as long as the iterator is used
+    ///
+    /// * `binary_size` - corresponds to a valid size of `binary`
+    pub(crate) unsafe fn new(
+        pattern: &'s Pattern,
+        preferred_scan_mode: Option<ScanMode>,
+        binary: *const u8,
+        binary_size: usize,
+    ) -> Self {
+        ScanResults {
+            pattern,
+            preferred_scan_mode,
+            binary,
+            binary_size,
+            position: 0,
+        }
+    }
+}
+
+impl ScanResults<'_> {
+    /// Find the start of the next match, ignoring the result offset of the pattern
+    fn next_start(&mut self) -> Option<ScanResult> {
+        if self.position > self.binary_size {
+            return None;
+        }
+
+        // SAFETY: `position` never exceeds `binary_size`, the remaining conditions
+        // were met when constructing the iterator
+        let result = unsafe {
+            backends::find(
+                self.pattern,
+                self.preferred_scan_mode,
+                self.binary.add(self.position),
+                self.binary_size - self.position,
+            )
+        };
+
+        if result.is_valid() {
+            // resume right after the start of the match, so overlapping matches are reported too
+            self.position = result.get_addr() as usize - self.binary as usize + 1;
+            Some(result)
+        } else {
+            self.position = self.binary_size + 1;
+            None
+        }
+    }
+}
+
+impl Iterator for ScanResults<'_> {
+    type Item = ScanResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.next_start()?;
+
+        // SAFETY: the result offset never exceeds the pattern size, so the address stays in binary bounds
+        Some(unsafe { result.shifted(self.pattern.result_offset) })
+    }
+}
+
+impl FusedIterator for ScanResults<'_> {}
+
+/// Iterator over every occurrence of a pattern in a haystack
+///
+/// Created by [`Scanner::find_all_in`](crate::Scanner::find_all_in).
+pub struct Matches<'s, 'a> {
+    inner: ScanResults<'s>,
+    haystack: &'a [u8],
+}
+
+impl<'s, 'a> Matches<'s, 'a> {
+    pub(crate) fn new(
+        pattern: &'s Pattern,
+        preferred_scan_mode: Option<ScanMode>,
+        haystack: &'a [u8],
+    ) -> Self {
+        // SAFETY: the pointer and size come from a valid slice that outlives the iterator
+        let inner = unsafe {
+            ScanResults::new(
+                pattern,
+                preferred_scan_mode,
+                haystack.as_ptr(),
+                haystack.len(),
+            )
+        };
+
+        Matches { inner, haystack }
+    }
+}
+
+impl<'a> Iterator for Matches<'_, 'a> {
+    type Item = Match<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.inner.next_start()?;
+
+        let start = result.get_addr() as usize - self.haystack.as_ptr() as usize;
+        Some(Match::new(self.haystack, start, self.inner.pattern))
+    }
+}
+
+impl FusedIterator for Matches<'_, '_> {}
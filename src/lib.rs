@@ -10,27 +10,62 @@
 //! let binary = [0xab, 0xec, 0x48, 0x89, 0x5c, 0x24, 0xee, 0x48, 0x89, 0x6c];
 //!
 //! let scanner = Scanner::new("48 89 5c 24 ?? 48 89 6c");
-//! let result = unsafe { scanner.find(None, binary.as_ptr(), binary.len()) };
+//! let result = scanner.find_in(&binary);
 //!
 //! println!("{:?}", result);
 //! ```
 #![deny(unsafe_op_in_unsafe_fn, clippy::undocumented_unsafe_blocks)]
 
+use crate::iter::{Matches, ScanResults};
 use crate::pattern::Pattern;
+use crate::x86::RelativeOperand;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use std::ptr;
 
 mod aligned_bytes;
 mod backends;
+pub mod dump;
+pub mod elf;
+#[cfg(feature = "mmap")]
+mod file;
+pub mod iter;
+pub mod macho;
+#[cfg(target_os = "linux")]
+pub mod module;
+mod multi;
+#[cfg(feature = "parallel")]
+mod parallel;
 pub mod pattern;
+pub mod pe;
+#[cfg(target_os = "linux")]
+pub mod process;
+mod reader;
+pub mod sig_maker;
+#[cfg(feature = "serde")]
+pub mod signature;
+mod stream;
+mod x86;
+
+pub use multi::MultiScanner;
+pub use stream::StreamScanner;
 
 /// Single result IDA-style pattern scanner
 ///
 /// A pattern scanner that searches for an IDA-style pattern
 /// and returns the pointer to the first occurrence in the binary.
+#[derive(Debug, Clone)]
 pub struct Scanner(Pattern);
 
 impl Scanner {
     /// Create a new [`Scanner`] instance
     ///
+    /// # Panics
+    ///
+    /// Panics if the pattern is malformed, see [`Pattern::parse`] for a fallible alternative.
+    ///
     /// # Example
     ///
     /// ```
@@ -43,12 +78,12 @@ impl Scanner {
     }
 
     /// Create a new [`Scanner`] instance, using a string literal pattern.
-    /// 
+    ///
     /// # Example
-    /// 
+    ///
     /// ```
     /// use lightningscanner::Scanner;
-    /// 
+    ///
     /// let scanner = Scanner::new_from_str("LocalPlayer");
     /// ```
     pub fn new_from_str(pattern: &str) -> Self {
@@ -60,7 +95,7 @@ impl Scanner {
     /// # Params
     ///
     /// * `preferred_scan_mode` - preferred scan mode to use (Avx2, Sse42, Scalar)
-    ///     if the preferred mode is not available, will choose the fastest out of the availble ones
+    ///   if the preferred mode is not available, will choose the fastest out of the availble ones
     ///
     /// * `binary_ptr` - pointer to the first element of the binary to search the pattern in
     ///
@@ -91,7 +126,298 @@ impl Scanner {
         binary_size: usize,
     ) -> ScanResult {
         // SAFETY: safe to call as long as the safety conditions were met for this function
-        unsafe { backends::find(&self.0, preferred_scan_mode, binary_ptr, binary_size) }
+        let result =
+            unsafe { backends::find(&self.0, preferred_scan_mode, binary_ptr, binary_size) };
+
+        // SAFETY: the result offset never exceeds the pattern size, so the address stays in binary bounds
+        unsafe { result.shifted(self.0.result_offset) }
+    }
+
+    /// Find the first occurence of the pattern in the haystack
+    ///
+    /// Safe alternative to [`Scanner::find`] that chooses the fastest available scan mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::Scanner;
+    ///
+    /// let binary = [0xab, 0xec, 0x48, 0x89, 0x5c, 0x24, 0xee, 0x48, 0x89, 0x6c];
+    ///
+    /// let scanner = Scanner::new("48 89 5c 24 ?? 48 89 6c");
+    /// let result = scanner.find_in(&binary).unwrap();
+    ///
+    /// assert_eq!(result.offset(), 2);
+    /// ```
+    pub fn find_in<'a>(&self, haystack: &'a [u8]) -> Option<Match<'a>> {
+        self.find_in_with_mode(None, haystack)
+    }
+
+    /// Find the first occurence of the pattern in the haystack using the preferred scan mode
+    ///
+    /// # Params
+    ///
+    /// * `preferred_scan_mode` - preferred scan mode to use (Avx2, Sse42, Scalar)
+    ///   if the preferred mode is not available, will choose the fastest out of the availble ones
+    ///
+    /// * `haystack` - binary to search the pattern in
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::{ScanMode, Scanner};
+    ///
+    /// let binary = [0xab, 0xec, 0x48, 0x89, 0x5c, 0x24, 0xee, 0x48, 0x89, 0x6c];
+    ///
+    /// let scanner = Scanner::new("48 89 5c 24 ?? 48 89 6c");
+    /// let result = scanner.find_in_with_mode(Some(ScanMode::Scalar), &binary).unwrap();
+    ///
+    /// assert_eq!(result.as_bytes(), &binary[2..]);
+    /// ```
+    pub fn find_in_with_mode<'a>(
+        &self,
+        preferred_scan_mode: Option<ScanMode>,
+        haystack: &'a [u8],
+    ) -> Option<Match<'a>> {
+        // SAFETY: the pointer and size come from a valid slice
+        let result = unsafe {
+            backends::find(
+                &self.0,
+                preferred_scan_mode,
+                haystack.as_ptr(),
+                haystack.len(),
+            )
+        };
+
+        if !result.is_valid() {
+            return None;
+        }
+
+        let start = result.get_addr() as usize - haystack.as_ptr() as usize;
+        Some(Match::new(haystack, start, &self.0))
+    }
+
+    /// Find every occurence of the pattern in the binary, including overlapping ones
+    ///
+    /// The binary is scanned lazily, each step resumes right after the start of the previous match.
+    ///
+    /// # Params
+    ///
+    /// * `preferred_scan_mode` - preferred scan mode to use (Avx2, Sse42, Scalar)
+    ///   if the preferred mode is not available, will choose the fastest out of the availble ones
+    ///
+    /// * `binary_ptr` - pointer to the first element of the binary to search the pattern in
+    ///
+    /// * `binary_size` - binary size
+    ///
+    /// # Safety
+    ///
+    /// * `binary_ptr` - is a valid pointer for as long as the returned iterator is used
+    ///
+    /// * `binary_size` - corresponds to a valid size of `binary`
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::Scanner;
+    ///
+    /// let binary = [0x48, 0x89, 0x5c, 0x48, 0x89, 0x6c, 0x48, 0x89];
+    ///
+    /// let scanner = Scanner::new("48 89");
+    /// let results = unsafe { scanner.find_all(None, binary.as_ptr(), binary.len()) };
+    ///
+    /// assert_eq!(results.count(), 3);
+    /// ```
+    pub unsafe fn find_all(
+        &self,
+        preferred_scan_mode: Option<ScanMode>,
+        binary_ptr: *const u8,
+        binary_size: usize,
+    ) -> ScanResults<'_> {
+        // SAFETY: safe to call as long as the safety conditions were met for this function
+        unsafe { ScanResults::new(&self.0, preferred_scan_mode, binary_ptr, binary_size) }
+    }
+
+    /// Find every occurence of the pattern in the haystack, including overlapping ones
+    ///
+    /// Safe alternative to [`Scanner::find_all`] that chooses the fastest available scan mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::Scanner;
+    ///
+    /// let binary = [0xaa, 0xaa, 0xaa, 0x48, 0xaa];
+    ///
+    /// let scanner = Scanner::new("aa aa");
+    /// let offsets = scanner
+    ///     .find_all_in(&binary)
+    ///     .map(|result| result.offset())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(offsets, [0, 1]);
+    /// ```
+    pub fn find_all_in<'a>(&self, haystack: &'a [u8]) -> Matches<'_, 'a> {
+        self.find_all_in_with_mode(None, haystack)
+    }
+
+    /// Find every occurence of the pattern in the haystack using the preferred scan mode
+    ///
+    /// # Params
+    ///
+    /// * `preferred_scan_mode` - preferred scan mode to use (Avx2, Sse42, Scalar)
+    ///   if the preferred mode is not available, will choose the fastest out of the availble ones
+    ///
+    /// * `haystack` - binary to search the pattern in
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::{ScanMode, Scanner};
+    ///
+    /// let binary = [0xe8, 0x10, 0x00, 0xe8, 0x20, 0x00];
+    ///
+    /// let scanner = Scanner::new("e8 ?? 00");
+    /// let results = scanner
+    ///     .find_all_in_with_mode(Some(ScanMode::Scalar), &binary)
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(results[1].as_bytes(), &[0xe8, 0x20, 0x00]);
+    /// ```
+    pub fn find_all_in_with_mode<'a>(
+        &self,
+        preferred_scan_mode: Option<ScanMode>,
+        haystack: &'a [u8],
+    ) -> Matches<'_, 'a> {
+        Matches::new(&self.0, preferred_scan_mode, haystack)
+    }
+
+    /// Find the only occurence of the pattern in the haystack
+    ///
+    /// Fails if the pattern doesn't occur exactly once, the error reports the number of occurences
+    /// and the offsets of the first few. The whole haystack is scanned even if the first match
+    /// is found early.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::Scanner;
+    ///
+    /// let binary = [0xe8, 0x10, 0x00, 0xe8, 0x20, 0x00];
+    ///
+    /// let result = Scanner::new("e8 20").find_unique(&binary).unwrap();
+    /// assert_eq!(result.offset(), 3);
+    ///
+    /// let err = Scanner::new("e8 ?? 00").find_unique(&binary).unwrap_err();
+    /// assert_eq!(err.count(), 2);
+    /// assert_eq!(err.offsets(), [0, 3]);
+    /// ```
+    pub fn find_unique<'a>(&self, haystack: &'a [u8]) -> Result<Match<'a>, UniquenessError> {
+        self.find_unique_with_mode(None, haystack)
+    }
+
+    /// Find the only occurence of the pattern in the haystack using the preferred scan mode
+    ///
+    /// # Params
+    ///
+    /// * `preferred_scan_mode` - preferred scan mode to use (Avx2, Sse42, Scalar)
+    ///   if the preferred mode is not available, will choose the fastest out of the availble ones
+    ///
+    /// * `haystack` - binary to search the pattern in
+    pub fn find_unique_with_mode<'a>(
+        &self,
+        preferred_scan_mode: Option<ScanMode>,
+        haystack: &'a [u8],
+    ) -> Result<Match<'a>, UniquenessError> {
+        let mut matches = self.find_all_in_with_mode(preferred_scan_mode, haystack);
+
+        let Some(first) = matches.next() else {
+            return Err(UniquenessError {
+                count: 0,
+                offsets: Vec::new(),
+            });
+        };
+
+        let mut offsets = vec![first.offset()];
+        let mut count = 1;
+
+        for result in matches {
+            if offsets.len() < UniquenessError::MAX_OFFSETS {
+                offsets.push(result.offset());
+            }
+            count += 1;
+        }
+
+        if count == 1 {
+            Ok(first)
+        } else {
+            Err(UniquenessError { count, offsets })
+        }
+    }
+
+    /// Count the occurences of the pattern in the haystack, including overlapping ones
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::Scanner;
+    ///
+    /// let binary = [0xaa, 0xaa, 0xaa, 0x48, 0xaa];
+    ///
+    /// assert_eq!(Scanner::new("aa aa").count(&binary), 2);
+    /// ```
+    pub fn count(&self, haystack: &[u8]) -> usize {
+        self.count_with_mode(None, haystack)
+    }
+
+    /// Count the occurences of the pattern in the haystack using the preferred scan mode
+    ///
+    /// # Params
+    ///
+    /// * `preferred_scan_mode` - preferred scan mode to use (Avx2, Sse42, Scalar)
+    ///   if the preferred mode is not available, will choose the fastest out of the availble ones
+    ///
+    /// * `haystack` - binary to search the pattern in
+    pub fn count_with_mode(&self, preferred_scan_mode: Option<ScanMode>, haystack: &[u8]) -> usize {
+        // SAFETY: the pointer and size come from a valid slice that outlives the iterator
+        let results = unsafe {
+            ScanResults::new(
+                &self.0,
+                preferred_scan_mode,
+                haystack.as_ptr(),
+                haystack.len(),
+            )
+        };
+
+        results.count()
+    }
+
+    /// Find the first occurence of the pattern in a range of the haystack
+    ///
+    /// The match is relative to the whole haystack, not to the range.
+    pub(crate) fn find_in_range<'a>(
+        &self,
+        haystack: &'a [u8],
+        range: Range<usize>,
+    ) -> Option<Match<'a>> {
+        let start = range.start;
+        let result = self.find_in(&haystack[range])?;
+
+        Some(Match::new(haystack, start + result.start(), &self.0))
+    }
+
+    /// Find every occurence of the pattern in a range of the haystack
+    ///
+    /// The matches are relative to the whole haystack, not to the range.
+    pub(crate) fn find_all_in_range<'s, 'a: 's>(
+        &'s self,
+        haystack: &'a [u8],
+        range: Range<usize>,
+    ) -> impl Iterator<Item = Match<'a>> + 's {
+        let start = range.start;
+
+        self.find_all_in(&haystack[range])
+            .map(move |result| Match::new(haystack, start + result.start(), &self.0))
     }
 }
 
@@ -101,6 +427,50 @@ impl From<Pattern> for Scanner {
     }
 }
 
+/// Error returned by [`Scanner::find_unique`] when a pattern doesn't occur exactly once
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UniquenessError {
+    count: usize,
+    offsets: Vec<usize>,
+}
+
+impl UniquenessError {
+    /// Maximum number of reported match offsets
+    const MAX_OFFSETS: usize = 8;
+
+    /// Get the number of occurences of the pattern, zero if it was not found
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Get the offsets of the first few occurences, including the result offset of the pattern
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+}
+
+impl fmt::Display for UniquenessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.count == 0 {
+            return f.write_str("pattern not found");
+        }
+
+        write!(f, "pattern found {} times, at", self.count)?;
+        for (i, offset) in self.offsets.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(f, "{separator}{offset:#x}")?;
+        }
+
+        if self.count > self.offsets.len() {
+            f.write_str(", ...")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Error for UniquenessError {}
+
 /// Scan mode
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum ScanMode {
@@ -112,6 +482,192 @@ pub enum ScanMode {
     Avx2,
 }
 
+/// Safe scan result
+///
+/// A match of a pattern inside of a haystack slice, produced by [`Scanner::find_in`].
+///
+/// Matches are compared and hashed by the identity of their haystack, not its contents.
+#[derive(Copy, Clone)]
+pub struct Match<'a> {
+    haystack: &'a [u8],
+    start: usize,
+    len: usize,
+    result_offset: usize,
+}
+
+impl<'a> Match<'a> {
+    pub(crate) fn new(haystack: &'a [u8], start: usize, pattern: &Pattern) -> Self {
+        debug_assert!(start + pattern.unpadded_size <= haystack.len());
+        Match {
+            haystack,
+            start,
+            len: pattern.unpadded_size,
+            result_offset: pattern.result_offset,
+        }
+    }
+
+    /// Get the offset of this result from the start of the haystack
+    ///
+    /// Points at the result-offset marker of the pattern if it has one,
+    /// otherwise equal to [`Match::start`].
+    pub fn offset(&self) -> usize {
+        self.start + self.result_offset
+    }
+
+    /// Get the offset of the first matched byte from the start of the haystack
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Get the range of the haystack covered by this match
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.start + self.len
+    }
+
+    /// Get the matched bytes
+    pub fn as_bytes(&self) -> &'a [u8] {
+        &self.haystack[self.range()]
+    }
+
+    /// Get the haystack this match was found in
+    pub fn haystack(&self) -> &'a [u8] {
+        self.haystack
+    }
+
+    /// Resolve a 32-bit relative displacement
+    ///
+    /// Reads the displacement `disp_offset` bytes past [`Match::offset`] and adds it to the end of the
+    /// instruction, which is `instr_len` bytes past [`Match::offset`].
+    ///
+    /// Returns the haystack offset of the target, or `None` if the displacement or the target
+    /// are out of haystack bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::Scanner;
+    ///
+    /// // mov rax, [rip + 0x2]; ret; int3; int3
+    /// let binary = [0x48, 0x8b, 0x05, 0x02, 0x00, 0x00, 0x00, 0xc3, 0xcc, 0xcc];
+    ///
+    /// let scanner = Scanner::new("48 8b 05 ?? ?? ?? ?? c3");
+    /// let result = scanner.find_in(&binary).unwrap();
+    ///
+    /// assert_eq!(result.resolve_rel32(3, 7), Some(9));
+    /// ```
+    pub fn resolve_rel32(&self, disp_offset: usize, instr_len: usize) -> Option<usize> {
+        self.resolve(RelativeOperand {
+            disp_offset,
+            disp_size: 4,
+            instr_len,
+        })
+    }
+
+    /// Resolve the target of a `call rel32` instruction at [`Match::offset`]
+    ///
+    /// Returns `None` if there is no `call rel32` instruction at the offset or the target
+    /// is out of haystack bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::Scanner;
+    ///
+    /// // call +0x1; int3; ret
+    /// let binary = [0xe8, 0x01, 0x00, 0x00, 0x00, 0xcc, 0xc3];
+    ///
+    /// let scanner = Scanner::new("e8 ?? ?? ?? ?? cc");
+    /// let result = scanner.find_in(&binary).unwrap();
+    ///
+    /// assert_eq!(result.resolve_call(), Some(6));
+    /// ```
+    pub fn resolve_call(&self) -> Option<usize> {
+        self.resolve(x86::decode_call(|i| self.byte(i))?)
+    }
+
+    /// Resolve the target of a `jmp` or `jcc` instruction at [`Match::offset`]
+    ///
+    /// Supports `jmp rel8`, `jmp rel32`, `jcc rel8` and `jcc rel32`.
+    /// Returns `None` if there is no such instruction at the offset or the target
+    /// is out of haystack bounds.
+    pub fn resolve_jmp(&self) -> Option<usize> {
+        self.resolve(x86::decode_jmp(|i| self.byte(i))?)
+    }
+
+    /// Resolve the RIP-relative memory operand of the instruction at [`Match::offset`]
+    ///
+    /// Supports instructions without an immediate operand, such as `mov`, `lea`, `cmp`, `movzx`
+    /// or `call/jmp qword ptr [rip + disp32]`.
+    /// Returns `None` if the instruction is not supported or the target is out of haystack bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::Scanner;
+    ///
+    /// // lea rcx, [rip + 0x1]; int3; ret
+    /// let binary = [0x48, 0x8d, 0x0d, 0x01, 0x00, 0x00, 0x00, 0xcc, 0xc3];
+    ///
+    /// let scanner = Scanner::new("48 8d 0d ?? ?? ?? ??");
+    /// let result = scanner.find_in(&binary).unwrap();
+    ///
+    /// assert_eq!(result.resolve_rip_relative(), Some(8));
+    /// ```
+    pub fn resolve_rip_relative(&self) -> Option<usize> {
+        self.resolve(x86::decode_rip_relative(|i| self.byte(i))?)
+    }
+
+    fn byte(&self, i: usize) -> Option<u8> {
+        self.haystack.get(self.offset() + i).copied()
+    }
+
+    fn resolve(&self, operand: RelativeOperand) -> Option<usize> {
+        let disp = operand.read_disp(|i| self.byte(i))?;
+        let target = (self.offset() + operand.instr_len) as i64 + disp;
+
+        usize::try_from(target)
+            .ok()
+            .filter(|&target| target < self.haystack.len())
+    }
+}
+
+impl fmt::Debug for Match<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // the haystack can be a whole binary, only the matched bytes are shown
+        f.debug_struct("Match")
+            .field("offset", &self.offset())
+            .field("range", &self.range())
+            .field("bytes", &self.as_bytes())
+            .finish()
+    }
+}
+
+impl Match<'_> {
+    fn key(&self) -> (*const u8, usize, usize, usize, usize) {
+        (
+            self.haystack.as_ptr(),
+            self.haystack.len(),
+            self.start,
+            self.len,
+            self.result_offset,
+        )
+    }
+}
+
+impl PartialEq for Match<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.key() == other.key()
+    }
+}
+
+impl Eq for Match<'_> {}
+
+impl Hash for Match<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
 /// Scan result
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct ScanResult {
@@ -129,6 +685,21 @@ impl ScanResult {
         self.addr
     }
 
+    /// Shift a valid result by `offset` bytes, invalid results stay invalid
+    ///
+    /// # Safety
+    ///
+    /// * The resulting address must be in bounds of the scanned binary
+    pub(crate) unsafe fn shifted(self, offset: usize) -> Self {
+        if !self.is_valid() {
+            return self;
+        }
+
+        // SAFETY: the caller must uphold the safety contract for `shifted`.
+        let addr = unsafe { self.addr.add(offset) };
+        ScanResult { addr }
+    }
+
     /// Get a pointer to the value
     ///
     /// Gets the result address, shifts by `offset` bytes and casts to *const T
@@ -152,6 +723,98 @@ impl ScanResult {
         unsafe { self.addr.offset(offset) as *const _ }
     }
 
+    /// Resolve a 32-bit relative displacement
+    ///
+    /// Reads the displacement `disp_offset` bytes past the result address and adds it to the end of the
+    /// instruction, which is `instr_len` bytes past the result address.
+    ///
+    /// `region` is the binary the result was found in. Returns an invalid result if the result
+    /// is invalid, or the result address, the displacement or the target are out of region bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::Scanner;
+    ///
+    /// // mov rax, [rip + 0x2]; ret; int3; int3
+    /// let binary = [0x48, 0x8b, 0x05, 0x02, 0x00, 0x00, 0x00, 0xc3, 0xcc, 0xcc];
+    ///
+    /// let scanner = Scanner::new("48 8b 05 ?? ?? ?? ?? c3");
+    /// let result = unsafe { scanner.find(None, binary.as_ptr(), binary.len()) };
+    /// let target = result.resolve_rel32(&binary, 3, 7);
+    ///
+    /// assert_eq!(target.get_addr(), binary[9..].as_ptr());
+    /// ```
+    pub fn resolve_rel32(&self, region: &[u8], disp_offset: usize, instr_len: usize) -> ScanResult {
+        self.resolve(region, |_| {
+            Some(RelativeOperand {
+                disp_offset,
+                disp_size: 4,
+                instr_len,
+            })
+        })
+    }
+
+    /// Resolve the target of a `call rel32` instruction at the result address
+    ///
+    /// `region` is the binary the result was found in. Returns an invalid result if there is
+    /// no `call rel32` instruction at the address, or the instruction or the target are out of
+    /// region bounds.
+    pub fn resolve_call(&self, region: &[u8]) -> ScanResult {
+        self.resolve(region, |byte| x86::decode_call(byte))
+    }
+
+    /// Resolve the target of a `jmp` or `jcc` instruction at the result address
+    ///
+    /// Supports `jmp rel8`, `jmp rel32`, `jcc rel8` and `jcc rel32`.
+    /// `region` is the binary the result was found in. Returns an invalid result if there is
+    /// no such instruction at the address, or the instruction or the target are out of
+    /// region bounds.
+    pub fn resolve_jmp(&self, region: &[u8]) -> ScanResult {
+        self.resolve(region, |byte| x86::decode_jmp(byte))
+    }
+
+    /// Resolve the RIP-relative memory operand of the instruction at the result address
+    ///
+    /// Supports instructions without an immediate operand, such as `mov`, `lea`, `cmp`, `movzx`
+    /// or `call/jmp qword ptr [rip + disp32]`.
+    /// `region` is the binary the result was found in. Returns an invalid result if the instruction
+    /// is not supported, or the instruction or the target are out of region bounds.
+    pub fn resolve_rip_relative(&self, region: &[u8]) -> ScanResult {
+        self.resolve(region, |byte| x86::decode_rip_relative(byte))
+    }
+
+    /// Resolve the operand returned by `decode` for the instruction at the result address,
+    /// all bytes are read through `region`
+    fn resolve(
+        &self,
+        region: &[u8],
+        decode: impl FnOnce(&dyn Fn(usize) -> Option<u8>) -> Option<RelativeOperand>,
+    ) -> ScanResult {
+        let offset = (self.addr as usize)
+            .checked_sub(region.as_ptr() as usize)
+            .filter(|&offset| self.is_valid() && offset < region.len());
+
+        let target = offset.and_then(|offset| {
+            let byte = |i: usize| region.get(offset + i).copied();
+
+            let operand = decode(&byte)?;
+            let disp = operand.read_disp(byte)?;
+            let target = (offset + operand.instr_len) as i64 + disp;
+
+            usize::try_from(target)
+                .ok()
+                .filter(|&target| target < region.len())
+        });
+
+        match target {
+            Some(target) => ScanResult {
+                addr: region[target..].as_ptr(),
+            },
+            None => ScanResult { addr: ptr::null() },
+        }
+    }
+
     /// Get a mutable pointer to the value
     ///
     /// Gets the result address, shifts by `offset` bytes and casts to *mut T
@@ -0,0 +1,509 @@
+//! Mach-O file parsing and section-restricted scanning
+
+use crate::reader::Reader;
+use crate::{Match, Scanner};
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+/// CPU type of x86-64 slices
+pub const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+/// CPU type of arm64 slices
+pub const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+
+const MH_MAGIC: u32 = 0xfeed_face;
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+const FAT_MAGIC: u32 = 0xcafe_babe;
+const FAT_MAGIC_64: u32 = 0xcafe_babf;
+
+const LC_SEGMENT: u32 = 0x1;
+const LC_SEGMENT_64: u32 = 0x19;
+
+const VM_PROT_READ: u32 = 0x1;
+const VM_PROT_WRITE: u32 = 0x2;
+const VM_PROT_EXECUTE: u32 = 0x4;
+
+const SECTION_TYPE: u32 = 0xff;
+const S_ZEROFILL: u32 = 0x1;
+const S_GB_ZEROFILL: u32 = 0xc;
+const S_THREAD_LOCAL_ZEROFILL: u32 = 0x12;
+const S_ATTR_PURE_INSTRUCTIONS: u32 = 0x8000_0000;
+const S_ATTR_SOME_INSTRUCTIONS: u32 = 0x0000_0400;
+
+/// Error returned when parsing a Mach-O file fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MachOError {
+    /// The file is neither a thin nor a fat Mach-O file
+    BadMagic,
+    /// The file contains no slice for the requested CPU type
+    ArchNotFound(u32),
+    /// A header, load command, segment or section lies outside of the file
+    Truncated,
+}
+
+impl fmt::Display for MachOError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MachOError::BadMagic => write!(f, "missing Mach-O magic"),
+            MachOError::ArchNotFound(cpu_type) => {
+                write!(f, "no slice for CPU type {cpu_type:#x}")
+            }
+            MachOError::Truncated => write!(f, "truncated Mach-O file"),
+        }
+    }
+}
+
+impl Error for MachOError {}
+
+/// A segment of a Mach-O image, as described by its `LC_SEGMENT` or `LC_SEGMENT_64` command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    name: String,
+    vmaddr: u64,
+    vmsize: u64,
+    offset: u64,
+    file_size: u64,
+    protection: u32,
+}
+
+impl Segment {
+    /// Get the name of the segment, such as `__TEXT`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the virtual address of the segment
+    pub fn vmaddr(&self) -> u64 {
+        self.vmaddr
+    }
+
+    /// Get the size of the segment in memory
+    pub fn vmsize(&self) -> u64 {
+        self.vmsize
+    }
+
+    /// Get the offsets of the segment data in the whole file
+    pub fn file_range(&self) -> Range<usize> {
+        self.offset as usize..self.offset.saturating_add(self.file_size) as usize
+    }
+
+    /// Check if the segment is initially readable
+    pub fn is_readable(&self) -> bool {
+        self.protection & VM_PROT_READ != 0
+    }
+
+    /// Check if the segment is initially writable
+    pub fn is_writable(&self) -> bool {
+        self.protection & VM_PROT_WRITE != 0
+    }
+
+    /// Check if the segment is initially executable
+    pub fn is_executable(&self) -> bool {
+        self.protection & VM_PROT_EXECUTE != 0
+    }
+}
+
+/// A section of a Mach-O image
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    segment_name: String,
+    name: String,
+    address: u64,
+    size: u64,
+    offset: u64,
+    flags: u32,
+}
+
+impl Section {
+    /// Get the name of the segment the section belongs to, such as `__TEXT`
+    pub fn segment_name(&self) -> &str {
+        &self.segment_name
+    }
+
+    /// Get the name of the section, such as `__text`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the virtual address of the section
+    pub fn address(&self) -> u64 {
+        self.address
+    }
+
+    /// Get the size of the section
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    /// Get the section flags
+    pub fn flags(&self) -> u32 {
+        self.flags
+    }
+
+    /// Check if the section has no data in the file and is zero-filled in memory
+    pub fn is_zerofill(&self) -> bool {
+        matches!(
+            self.flags & SECTION_TYPE,
+            S_ZEROFILL | S_GB_ZEROFILL | S_THREAD_LOCAL_ZEROFILL
+        )
+    }
+
+    /// Check if the section contains machine instructions
+    pub fn is_executable(&self) -> bool {
+        self.flags & (S_ATTR_PURE_INSTRUCTIONS | S_ATTR_SOME_INSTRUCTIONS) != 0
+    }
+
+    /// Get the offsets of the section data in the whole file, `None` for zero-filled sections
+    pub fn file_range(&self) -> Option<Range<usize>> {
+        if self.is_zerofill() {
+            return None;
+        }
+
+        Some(self.offset as usize..self.offset.saturating_add(self.size) as usize)
+    }
+}
+
+/// A parsed Mach-O image, either a thin file or a single slice of a fat (universal) file
+///
+/// File offsets, including match offsets, are relative to the start of the whole file,
+/// so they stay valid for slices of fat files.
+///
+/// # Example
+///
+/// ```no_run
+/// use lightningscanner::macho::{MachO, CPU_TYPE_ARM64};
+/// use lightningscanner::Scanner;
+///
+/// let data = std::fs::read("Game.app/Contents/MacOS/Game").unwrap();
+/// let macho = MachO::parse_arch(&data, CPU_TYPE_ARM64).unwrap();
+///
+/// let scanner = Scanner::new("fd 7b bf a9 fd 03 00 91");
+/// if let Some(result) = macho.find_in_section(&scanner, "__TEXT", "__text") {
+///     println!("found at {:#x}", macho.offset_to_vmaddr(result.offset()).unwrap());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct MachO<'a> {
+    data: &'a [u8],
+    slice: Range<usize>,
+    is_64: bool,
+    big_endian: bool,
+    cpu_type: u32,
+    cpu_subtype: u32,
+    file_type: u32,
+    segments: Vec<Segment>,
+    sections: Vec<Section>,
+}
+
+impl<'a> MachO<'a> {
+    /// Parse a thin Mach-O file, or the first slice of a fat file
+    pub fn parse(data: &'a [u8]) -> Result<Self, MachOError> {
+        Self::parse_all(data)?
+            .into_iter()
+            .next()
+            .ok_or(MachOError::Truncated)
+    }
+
+    /// Parse the slice for the given CPU type, such as [`CPU_TYPE_X86_64`]
+    ///
+    /// A thin file is returned if it is built for the CPU type.
+    pub fn parse_arch(data: &'a [u8], cpu_type: u32) -> Result<Self, MachOError> {
+        Self::parse_all(data)?
+            .into_iter()
+            .find(|image| image.cpu_type == cpu_type)
+            .ok_or(MachOError::ArchNotFound(cpu_type))
+    }
+
+    /// Parse every slice of a fat file, or the only image of a thin file
+    pub fn parse_all(data: &'a [u8]) -> Result<Vec<Self>, MachOError> {
+        let reader = Reader::new(data, true);
+
+        let is_64 = match reader.u32(0).ok_or(MachOError::BadMagic)? {
+            FAT_MAGIC => false,
+            FAT_MAGIC_64 => true,
+            _ => return Ok(vec![Self::parse_image(data, 0..data.len())?]),
+        };
+
+        let count = reader.u32(4).ok_or(MachOError::Truncated)? as usize;
+        let entry_size = if is_64 { 0x20 } else { 0x14 };
+
+        (0..count)
+            .map(|index| {
+                let entry = 8 + index * entry_size;
+                let slice = if is_64 {
+                    reader.u64(entry + 0x8).zip(reader.u64(entry + 0x10))
+                } else {
+                    reader
+                        .u32(entry + 0x8)
+                        .map(u64::from)
+                        .zip(reader.u32(entry + 0xc).map(u64::from))
+                };
+
+                let (offset, size) = slice.ok_or(MachOError::Truncated)?;
+                let end = offset.checked_add(size).ok_or(MachOError::Truncated)?;
+                if end > data.len() as u64 {
+                    return Err(MachOError::Truncated);
+                }
+
+                Self::parse_image(data, offset as usize..end as usize)
+            })
+            .collect()
+    }
+
+    /// Parse the thin image in `slice` of the file
+    fn parse_image(data: &'a [u8], slice: Range<usize>) -> Result<Self, MachOError> {
+        let image = &data[slice.clone()];
+
+        let (is_64, big_endian) = match Reader::new(image, false).u32(0) {
+            Some(MH_MAGIC) => (false, false),
+            Some(MH_MAGIC_64) => (true, false),
+            Some(magic) if magic.swap_bytes() == MH_MAGIC => (false, true),
+            Some(magic) if magic.swap_bytes() == MH_MAGIC_64 => (true, true),
+            _ => return Err(MachOError::BadMagic),
+        };
+
+        let macho = Self::parse_load_commands(data, slice, is_64, big_endian)
+            .ok_or(MachOError::Truncated)?;
+
+        let in_bounds =
+            |range: Range<usize>| range.start <= range.end && range.end <= macho.slice.end;
+        let segments_in_bounds = macho
+            .segments
+            .iter()
+            .map(Segment::file_range)
+            .all(in_bounds);
+        let sections_in_bounds = macho
+            .sections
+            .iter()
+            .filter_map(Section::file_range)
+            .all(in_bounds);
+
+        if !segments_in_bounds || !sections_in_bounds {
+            return Err(MachOError::Truncated);
+        }
+
+        Ok(macho)
+    }
+
+    fn parse_load_commands(
+        data: &'a [u8],
+        slice: Range<usize>,
+        is_64: bool,
+        big_endian: bool,
+    ) -> Option<Self> {
+        let reader = Reader::new(&data[slice.clone()], big_endian);
+        let base = slice.start as u64;
+
+        let cpu_type = reader.u32(0x4)?;
+        let cpu_subtype = reader.u32(0x8)?;
+        let file_type = reader.u32(0xc)?;
+        let command_count = reader.u32(0x10)? as usize;
+
+        let name = |offset: usize| {
+            let name = reader.bytes(offset, 16)?;
+            let len = name.iter().position(|&byte| byte == 0).unwrap_or(16);
+
+            Some(String::from_utf8_lossy(&name[..len]).into_owned())
+        };
+
+        let mut segments = Vec::new();
+        let mut sections = Vec::new();
+
+        let mut command = if is_64 { 0x20 } else { 0x1c };
+        for _ in 0..command_count {
+            let kind = reader.u32(command)?;
+            let size = reader.u32(command + 0x4)? as usize;
+
+            if kind == LC_SEGMENT_64 || kind == LC_SEGMENT {
+                let is_64 = kind == LC_SEGMENT_64;
+                let word = |offset: usize| reader.word(offset, is_64);
+                let (word_size, header_size, section_size) = if is_64 {
+                    (8, 0x48, 0x50)
+                } else {
+                    (4, 0x38, 0x44)
+                };
+
+                let fields = command + 0x18;
+                segments.push(Segment {
+                    name: name(command + 0x8)?,
+                    vmaddr: word(fields)?,
+                    vmsize: word(fields + word_size)?,
+                    offset: base + word(fields + 2 * word_size)?,
+                    file_size: word(fields + 3 * word_size)?,
+                    protection: reader.u32(fields + 4 * word_size + 0x4)?,
+                });
+
+                let section_count = reader.u32(fields + 4 * word_size + 0x8)? as usize;
+                for index in 0..section_count {
+                    let section = command + header_size + index * section_size;
+                    let fields = section + 0x20;
+
+                    sections.push(Section {
+                        name: name(section)?,
+                        segment_name: name(section + 0x10)?,
+                        address: word(fields)?,
+                        size: word(fields + word_size)?,
+                        offset: base + reader.u32(fields + 2 * word_size)? as u64,
+                        flags: reader.u32(fields + 2 * word_size + 0x10)?,
+                    });
+                }
+            }
+
+            if size == 0 {
+                return None;
+            }
+            command = command.checked_add(size)?;
+        }
+
+        Some(MachO {
+            data,
+            slice,
+            is_64,
+            big_endian,
+            cpu_type,
+            cpu_subtype,
+            file_type,
+            segments,
+            sections,
+        })
+    }
+
+    /// Get the raw data of the whole file
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Get the offsets of the image in the whole file, covering the whole file for thin files
+    pub fn slice_range(&self) -> Range<usize> {
+        self.slice.clone()
+    }
+
+    /// Check if the image is a 64-bit image
+    pub fn is_64(&self) -> bool {
+        self.is_64
+    }
+
+    /// Check if the image is big endian
+    pub fn is_big_endian(&self) -> bool {
+        self.big_endian
+    }
+
+    /// Get the CPU type of the image, such as [`CPU_TYPE_X86_64`]
+    pub fn cpu_type(&self) -> u32 {
+        self.cpu_type
+    }
+
+    /// Get the CPU subtype of the image
+    pub fn cpu_subtype(&self) -> u32 {
+        self.cpu_subtype
+    }
+
+    /// Get the file type of the image, such as `MH_EXECUTE` or `MH_DYLIB`
+    pub fn file_type(&self) -> u32 {
+        self.file_type
+    }
+
+    /// Get all segments of the image
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Get the segment with the given name
+    pub fn segment(&self, name: &str) -> Option<&Segment> {
+        self.segments.iter().find(|segment| segment.name == name)
+    }
+
+    /// Get all sections of the image
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// Get the section with the given segment and section name, such as `__TEXT`, `__text`
+    pub fn section(&self, segment_name: &str, name: &str) -> Option<&Section> {
+        self.sections
+            .iter()
+            .find(|section| section.segment_name == segment_name && section.name == name)
+    }
+
+    /// Get the data of a section, empty for zero-filled sections
+    pub fn section_data(&self, section: &Section) -> &'a [u8] {
+        section
+            .file_range()
+            .map_or(&[][..], |range| &self.data[range])
+    }
+
+    /// Convert a file offset to the virtual address it is loaded at
+    ///
+    /// Returns `None` if the offset is not part of a segment of this image.
+    pub fn offset_to_vmaddr(&self, offset: usize) -> Option<u64> {
+        self.segments
+            .iter()
+            .find(|segment| segment.file_range().contains(&offset))
+            .map(|segment| segment.vmaddr + (offset as u64 - segment.offset))
+    }
+
+    /// Convert a virtual address to the file offset it is loaded from
+    ///
+    /// Returns `None` if the address is not backed by file data of a segment of this image.
+    pub fn vmaddr_to_offset(&self, vmaddr: u64) -> Option<usize> {
+        self.segments
+            .iter()
+            .find(|segment| vmaddr >= segment.vmaddr && vmaddr - segment.vmaddr < segment.file_size)
+            .map(|segment| (segment.offset + (vmaddr - segment.vmaddr)) as usize)
+    }
+
+    /// Find the first occurence of the pattern in a section
+    ///
+    /// The match offset is a file offset, see [`MachO::offset_to_vmaddr`] to get its virtual address.
+    pub fn find_in_section(
+        &self,
+        scanner: &Scanner,
+        segment_name: &str,
+        name: &str,
+    ) -> Option<Match<'a>> {
+        let range = self.section(segment_name, name)?.file_range()?;
+        scanner.find_in_range(self.data, range)
+    }
+
+    /// Find every occurence of the pattern in a section
+    ///
+    /// The match offsets are file offsets, see [`MachO::offset_to_vmaddr`] to get their virtual addresses.
+    pub fn find_all_in_section(
+        &self,
+        scanner: &Scanner,
+        segment_name: &str,
+        name: &str,
+    ) -> Vec<Match<'a>> {
+        match self
+            .section(segment_name, name)
+            .and_then(Section::file_range)
+        {
+            Some(range) => scanner.find_all_in_range(self.data, range).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Find the first occurence of the pattern in the sections containing instructions
+    ///
+    /// The match offset is a file offset, see [`MachO::offset_to_vmaddr`] to get its virtual address.
+    pub fn find_in_executable_sections(&self, scanner: &Scanner) -> Option<Match<'a>> {
+        self.sections
+            .iter()
+            .filter(|section| section.is_executable())
+            .filter_map(Section::file_range)
+            .find_map(|range| scanner.find_in_range(self.data, range))
+    }
+
+    /// Find every occurence of the pattern in the sections containing instructions
+    ///
+    /// The match offsets are file offsets, see [`MachO::offset_to_vmaddr`] to get their virtual addresses.
+    pub fn find_all_in_executable_sections(&self, scanner: &Scanner) -> Vec<Match<'a>> {
+        self.sections
+            .iter()
+            .filter(|section| section.is_executable())
+            .filter_map(Section::file_range)
+            .flat_map(|range| scanner.find_all_in_range(self.data, range))
+            .collect()
+    }
+}
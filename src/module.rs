@@ -0,0 +1,276 @@
+//! Scanning of the modules loaded into the current process on Linux
+
+use crate::{ScanResult, Scanner};
+use std::ffi::{c_int, c_void, CStr, CString};
+use std::ops::Range;
+use std::path::Path;
+use std::{fmt, ptr};
+
+/// A loadable segment of a module, as described by its `PT_LOAD` program header
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    range: Range<usize>,
+    flags: u32,
+}
+
+impl Segment {
+    /// Get the start address of the segment
+    pub fn start(&self) -> usize {
+        self.range.start
+    }
+
+    /// Get the end address of the segment, exclusive
+    pub fn end(&self) -> usize {
+        self.range.end
+    }
+
+    /// Get the address range of the segment
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Get the size of the segment in memory
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    /// Check if the segment is empty
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    /// Check if the segment can be read
+    pub fn is_readable(&self) -> bool {
+        self.flags & libc::PF_R != 0
+    }
+
+    /// Check if the segment can be written to
+    pub fn is_writable(&self) -> bool {
+        self.flags & libc::PF_W != 0
+    }
+
+    /// Check if the segment can be executed
+    pub fn is_executable(&self) -> bool {
+        self.flags & libc::PF_X != 0
+    }
+}
+
+/// A module loaded into the current process, such as the main program or a shared library
+///
+/// The module is kept loaded for as long as the [`Module`] instance is alive.
+///
+/// # Example
+///
+/// ```
+/// use lightningscanner::module::Module;
+///
+/// let libc = Module::find("libc.so.6").unwrap();
+///
+/// assert!(libc.size() > 0);
+/// assert!(libc.executable_segments().count() > 0);
+/// ```
+pub struct Module {
+    name: String,
+    base: usize,
+    size: usize,
+    segments: Vec<Segment>,
+    /// Handle keeping the module loaded, released on drop
+    handle: *mut c_void,
+}
+
+// SAFETY: the dynamic loader handle may be used and released from any thread
+unsafe impl Send for Module {}
+
+// SAFETY: the handle is never used through a shared reference
+unsafe impl Sync for Module {}
+
+impl Module {
+    /// Find a loaded module by its file name, such as `libc.so.6`, or by its full path
+    ///
+    /// Returns `None` if no such module is loaded.
+    pub fn find(name: &str) -> Option<Module> {
+        let (module_name, base, size, segments) =
+            loaded_modules().into_iter().find(|(module_name, ..)| {
+                let path = Path::new(module_name);
+                module_name == name || path.file_name().is_some_and(|file_name| file_name == name)
+            })?;
+
+        let path = CString::new(module_name.clone()).ok()?;
+
+        // SAFETY: `path` is a valid nul-terminated string, `RTLD_NOLOAD` only takes a reference
+        // to an already loaded module
+        let handle = unsafe { libc::dlopen(path.as_ptr(), libc::RTLD_LAZY | libc::RTLD_NOLOAD) };
+        if handle.is_null() {
+            return None;
+        }
+
+        Some(Module {
+            name: module_name,
+            base,
+            size,
+            segments,
+            handle,
+        })
+    }
+
+    /// Get the main program of the current process
+    pub fn main() -> Module {
+        let (name, base, size, segments) = loaded_modules()
+            .into_iter()
+            .next()
+            .expect("the main program is always loaded");
+
+        // SAFETY: a null path returns a handle to the main program, which is never unloaded
+        let handle = unsafe { libc::dlopen(ptr::null(), libc::RTLD_LAZY) };
+
+        Module {
+            name,
+            base,
+            size,
+            segments,
+            handle,
+        }
+    }
+
+    /// Get the name of the module as reported by the dynamic loader, empty for the main program
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the address the first segment of the module is loaded at
+    pub fn base(&self) -> usize {
+        self.base
+    }
+
+    /// Get the size of the module in memory, from its base to the end of the last segment
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Get the address range of the module
+    pub fn range(&self) -> Range<usize> {
+        self.base..self.base + self.size
+    }
+
+    /// Get the loadable segments of the module
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Get the readable and executable segments of the module
+    pub fn executable_segments(&self) -> impl Iterator<Item = &Segment> {
+        self.segments
+            .iter()
+            .filter(|segment| segment.is_readable() && segment.is_executable())
+    }
+}
+
+impl fmt::Debug for Module {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Module")
+            .field("name", &self.name)
+            .field("base", &format_args!("{:#x}", self.base))
+            .field("size", &format_args!("{:#x}", self.size))
+            .field("segments", &self.segments)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Module {
+    fn drop(&mut self) {
+        if !self.handle.is_null() {
+            // SAFETY: the handle was returned by `dlopen` and is released exactly once
+            unsafe { libc::dlclose(self.handle) };
+        }
+    }
+}
+
+impl Scanner {
+    /// Find the first occurence of the pattern in the executable segments of a module
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::module::Module;
+    /// use lightningscanner::Scanner;
+    ///
+    /// let libc = Module::find("libc.so.6").unwrap();
+    ///
+    /// // ret
+    /// let scanner = Scanner::new("c3");
+    /// let result = scanner.find_in_module(&libc);
+    ///
+    /// assert!(result.is_valid());
+    /// assert!(libc.range().contains(&(result.get_addr() as usize)));
+    /// ```
+    pub fn find_in_module(&self, module: &Module) -> ScanResult {
+        for segment in module.executable_segments() {
+            // SAFETY: the module is kept loaded while `module` is alive,
+            // and its readable segments are mapped for their whole size
+            let result = unsafe { self.find(None, segment.start() as *const u8, segment.len()) };
+
+            if result.is_valid() {
+                return result;
+            }
+        }
+
+        ScanResult { addr: ptr::null() }
+    }
+}
+
+/// Name, base, size and segments of every loaded module, the main program first
+type ModuleInfo = (String, usize, usize, Vec<Segment>);
+
+/// List the modules loaded into the current process using `dl_iterate_phdr`
+fn loaded_modules() -> Vec<ModuleInfo> {
+    unsafe extern "C" fn callback(
+        info: *mut libc::dl_phdr_info,
+        _size: usize,
+        data: *mut c_void,
+    ) -> c_int {
+        // SAFETY: `data` is the vector passed to `dl_iterate_phdr` below,
+        // `info` is valid for the duration of the callback
+        let (modules, info) = unsafe { (&mut *(data as *mut Vec<ModuleInfo>), &*info) };
+
+        let name = if info.dlpi_name.is_null() {
+            String::new()
+        } else {
+            // SAFETY: the loader provides a valid nul-terminated module name
+            unsafe { CStr::from_ptr(info.dlpi_name) }
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        let headers = if info.dlpi_phdr.is_null() {
+            &[][..]
+        } else {
+            // SAFETY: the loader provides `dlpi_phnum` program headers at `dlpi_phdr`
+            unsafe { std::slice::from_raw_parts(info.dlpi_phdr, info.dlpi_phnum as usize) }
+        };
+
+        let segments = headers
+            .iter()
+            .filter(|header| header.p_type == libc::PT_LOAD)
+            .map(|header| {
+                let start = info.dlpi_addr as usize + header.p_vaddr as usize;
+                Segment {
+                    range: start..start + header.p_memsz as usize,
+                    flags: header.p_flags,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let base = segments.iter().map(Segment::start).min().unwrap_or(0);
+        let end = segments.iter().map(Segment::end).max().unwrap_or(0);
+
+        modules.push((name, base, end - base, segments));
+        0
+    }
+
+    let mut modules = Vec::<ModuleInfo>::new();
+
+    // SAFETY: the callback only accesses the data passed to it for the duration of the call
+    unsafe { libc::dl_iterate_phdr(Some(callback), &mut modules as *mut _ as *mut c_void) };
+
+    modules
+}
@@ -0,0 +1,299 @@
+//! Multi-pattern scanner
+
+use crate::backends::{self, ByteSet};
+use crate::pattern::Pattern;
+use crate::{Match, ScanMode, ScanResult};
+use std::ops::ControlFlow;
+use std::{fmt, ptr, slice};
+
+/// Single pass multi-pattern scanner
+///
+/// Searches for many patterns at once and returns the first occurrence of every pattern.
+/// Each pattern is anchored on its rarest fully masked byte, a shared SIMD prefilter finds
+/// the positions of all anchors in one pass over the binary before the candidate patterns are verified.
+///
+/// Patterns are identified by the index returned from [`MultiScanner::push`],
+/// results are returned in the same order.
+#[derive(Clone)]
+pub struct MultiScanner {
+    patterns: Vec<Pattern>,
+    anchors: ByteSet,
+    /// Anchored patterns by anchor byte value, as pairs of pattern index and anchor offset
+    buckets: Vec<Vec<(usize, usize)>>,
+    /// Patterns without a fully masked byte, which are scanned separately
+    unanchored: Vec<usize>,
+}
+
+impl MultiScanner {
+    /// Create a new empty [`MultiScanner`] instance
+    pub fn new() -> Self {
+        MultiScanner {
+            patterns: Vec::new(),
+            anchors: ByteSet::new(),
+            buckets: vec![Vec::new(); 256],
+            unanchored: Vec::new(),
+        }
+    }
+
+    /// Add a pattern to the scanner, returning its index
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::pattern::Pattern;
+    /// use lightningscanner::MultiScanner;
+    ///
+    /// let mut scanner = MultiScanner::new();
+    ///
+    /// assert_eq!(scanner.push(Pattern::new("48 89 5c 24 ??")), 0);
+    /// assert_eq!(scanner.push(Pattern::new("e8 ?? ?? ?? ??")), 1);
+    /// ```
+    pub fn push(&mut self, pattern: Pattern) -> usize {
+        let index = self.patterns.len();
+
+        match pattern.rare_bytes {
+            Some(rare_bytes) => {
+                let offset = rare_bytes.offsets[0];
+                let byte = rare_bytes.bytes[0];
+
+                self.anchors.insert(byte);
+                self.buckets[byte as usize].push((index, offset));
+            }
+            None => self.unanchored.push(index),
+        }
+
+        self.patterns.push(pattern);
+        index
+    }
+
+    /// Get the number of patterns in the scanner
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Check if the scanner contains no patterns
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Get the pattern with the given index
+    pub fn get(&self, index: usize) -> Option<&Pattern> {
+        self.patterns.get(index)
+    }
+
+    /// Get the size of the longest pattern in the scanner
+    pub(crate) fn max_pattern_size(&self) -> usize {
+        self.patterns
+            .iter()
+            .map(|pattern| pattern.unpadded_size)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Find the first occurence of every pattern in the binary
+    ///
+    /// Returns a result for every pattern, in the order the patterns were added.
+    ///
+    /// # Params
+    ///
+    /// * `preferred_scan_mode` - preferred scan mode to use (Avx2, Sse42, Scalar)
+    ///   if the preferred mode is not available, will choose the fastest out of the availble ones
+    ///
+    /// * `binary_ptr` - pointer to the first element of the binary to search the patterns in
+    ///
+    /// * `binary_size` - binary size
+    ///
+    /// # Safety
+    ///
+    /// * `binary_ptr` - is a valid pointer
+    ///
+    /// * `binary_size` - corresponds to a valid size of `binary`
+    pub unsafe fn find(
+        &self,
+        preferred_scan_mode: Option<ScanMode>,
+        binary_ptr: *const u8,
+        binary_size: usize,
+    ) -> Vec<ScanResult> {
+        // SAFETY: the caller must uphold the safety contract for `find`.
+        let binary = unsafe { slice::from_raw_parts(binary_ptr, binary_size) };
+
+        self.find_starts(preferred_scan_mode, binary)
+            .into_iter()
+            .zip(&self.patterns)
+            .map(|(start, pattern)| match start {
+                Some(start) => {
+                    // SAFETY: the result offset never exceeds the pattern size, so the address stays in binary bounds
+                    let addr = unsafe { binary_ptr.add(start + pattern.result_offset) };
+                    ScanResult { addr }
+                }
+                None => ScanResult { addr: ptr::null() },
+            })
+            .collect()
+    }
+
+    /// Find the first occurence of every pattern in the haystack
+    ///
+    /// Returns a result for every pattern, in the order the patterns were added.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::pattern::Pattern;
+    /// use lightningscanner::MultiScanner;
+    ///
+    /// let binary = [0xab, 0xec, 0x48, 0x89, 0x5c, 0x24, 0xee, 0xe8, 0x10, 0x00];
+    ///
+    /// let scanner = ["48 89 5c 24 ??", "e8 ?? 00", "48 8b"]
+    ///     .into_iter()
+    ///     .map(Pattern::new)
+    ///     .collect::<MultiScanner>();
+    ///
+    /// let results = scanner.find_in(&binary);
+    ///
+    /// assert_eq!(results[0].unwrap().offset(), 2);
+    /// assert_eq!(results[1].unwrap().offset(), 7);
+    /// assert!(results[2].is_none());
+    /// ```
+    pub fn find_in<'a>(&self, haystack: &'a [u8]) -> Vec<Option<Match<'a>>> {
+        self.find_in_with_mode(None, haystack)
+    }
+
+    /// Find the first occurence of every pattern in the haystack using the preferred scan mode
+    ///
+    /// Returns a result for every pattern, in the order the patterns were added.
+    ///
+    /// # Params
+    ///
+    /// * `preferred_scan_mode` - preferred scan mode to use (Avx2, Sse42, Scalar)
+    ///   if the preferred mode is not available, will choose the fastest out of the availble ones
+    ///
+    /// * `haystack` - binary to search the patterns in
+    pub fn find_in_with_mode<'a>(
+        &self,
+        preferred_scan_mode: Option<ScanMode>,
+        haystack: &'a [u8],
+    ) -> Vec<Option<Match<'a>>> {
+        self.find_starts(preferred_scan_mode, haystack)
+            .into_iter()
+            .zip(&self.patterns)
+            .map(|(start, pattern)| start.map(|start| Match::new(haystack, start, pattern)))
+            .collect()
+    }
+
+    /// Find the start of the first occurence of every pattern
+    fn find_starts(
+        &self,
+        preferred_scan_mode: Option<ScanMode>,
+        binary: &[u8],
+    ) -> Vec<Option<usize>> {
+        let mut starts = vec![None; self.patterns.len()];
+
+        for &index in &self.unanchored {
+            // SAFETY: the pointer and size come from a valid slice
+            let result = unsafe {
+                backends::find(
+                    &self.patterns[index],
+                    preferred_scan_mode,
+                    binary.as_ptr(),
+                    binary.len(),
+                )
+            };
+
+            if result.is_valid() {
+                starts[index] = Some(result.get_addr() as usize - binary.as_ptr() as usize);
+            }
+        }
+
+        let scan_mode = backends::scan_mode(preferred_scan_mode);
+        let mut anchors = self.anchors.clone();
+        // patterns not found yet, by anchor byte value
+        let mut pending = self
+            .buckets
+            .iter()
+            .map(|bucket| bucket.len())
+            .collect::<Vec<_>>();
+        let mut remaining = self.patterns.len() - self.unanchored.len();
+        let mut position = 0;
+
+        while remaining > 0 && position < binary.len() {
+            // SAFETY: the pointer and size come from a valid slice, `position` is in bounds
+            let stopped = unsafe {
+                backends::find_bytes(
+                    &anchors,
+                    scan_mode,
+                    binary.as_ptr().add(position),
+                    binary.len() - position,
+                    |candidate| {
+                        let candidate = position + candidate;
+                        let byte = binary[candidate] as usize;
+
+                        for &(index, anchor_offset) in &self.buckets[byte] {
+                            if starts[index].is_some() || candidate < anchor_offset {
+                                continue;
+                            }
+
+                            let start = candidate - anchor_offset;
+                            if self.patterns[index].matches(&binary[start..]) {
+                                starts[index] = Some(start);
+                                pending[byte] -= 1;
+                                remaining -= 1;
+                            }
+                        }
+
+                        // stop to drop the anchor from the set once all of its patterns are found
+                        if pending[byte] == 0 {
+                            ControlFlow::Break(())
+                        } else {
+                            ControlFlow::Continue(())
+                        }
+                    },
+                )
+            };
+
+            let Some(stopped) = stopped else {
+                break;
+            };
+
+            anchors = ByteSet::new();
+            for byte in (0..=u8::MAX).filter(|&byte| pending[byte as usize] > 0) {
+                anchors.insert(byte);
+            }
+
+            position += stopped + 1;
+        }
+
+        starts
+    }
+}
+
+impl Default for MultiScanner {
+    fn default() -> Self {
+        MultiScanner::new()
+    }
+}
+
+impl fmt::Debug for MultiScanner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultiScanner")
+            .field("patterns", &self.patterns)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FromIterator<Pattern> for MultiScanner {
+    fn from_iter<T: IntoIterator<Item = Pattern>>(iter: T) -> Self {
+        let mut scanner = MultiScanner::new();
+        for pattern in iter {
+            scanner.push(pattern);
+        }
+        scanner
+    }
+}
+
+impl Extend<Pattern> for MultiScanner {
+    fn extend<T: IntoIterator<Item = Pattern>>(&mut self, iter: T) {
+        for pattern in iter {
+            self.push(pattern);
+        }
+    }
+}
@@ -0,0 +1,112 @@
+//! Multi-threaded scanning of large regions
+
+use crate::{Match, ScanMode, Scanner};
+use rayon::prelude::*;
+
+/// Number of match starts scanned by a single task
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
+impl Scanner {
+    /// Find the first occurence of the pattern in the haystack using a thread pool
+    ///
+    /// The haystack is split into chunks which are scanned in parallel on the global rayon thread pool,
+    /// the earliest match is returned, same as [`Scanner::find_in_with_mode`].
+    ///
+    /// # Params
+    ///
+    /// * `preferred_scan_mode` - preferred scan mode to use (Avx2, Sse42, Scalar)
+    ///   if the preferred mode is not available, will choose the fastest out of the availble ones
+    ///
+    /// * `haystack` - binary to search the pattern in
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::Scanner;
+    ///
+    /// let binary = [0xe8, 0x10, 0x00, 0xe8, 0x20, 0x00];
+    ///
+    /// let scanner = Scanner::new("e8 ?? 00");
+    /// let result = scanner.par_find(None, &binary).unwrap();
+    ///
+    /// assert_eq!(result.offset(), 0);
+    /// ```
+    pub fn par_find<'a>(
+        &self,
+        preferred_scan_mode: Option<ScanMode>,
+        haystack: &'a [u8],
+    ) -> Option<Match<'a>> {
+        if haystack.len() <= CHUNK_SIZE || self.0.unpadded_size == 0 {
+            return self.find_in_with_mode(preferred_scan_mode, haystack);
+        }
+
+        chunks(haystack.len(), self.0.unpadded_size)
+            .into_par_iter()
+            .find_map_first(|(chunk_start, chunk_end)| {
+                self.find_in_with_mode(preferred_scan_mode, &haystack[chunk_start..chunk_end])
+                    .map(|result| Match::new(haystack, chunk_start + result.start(), &self.0))
+            })
+    }
+
+    /// Find every occurence of the pattern in the haystack using a thread pool
+    ///
+    /// The haystack is split into chunks which are scanned in parallel on the global rayon thread pool,
+    /// matches are returned in ascending order, same as [`Scanner::find_all_in_with_mode`].
+    ///
+    /// # Params
+    ///
+    /// * `preferred_scan_mode` - preferred scan mode to use (Avx2, Sse42, Scalar)
+    ///   if the preferred mode is not available, will choose the fastest out of the availble ones
+    ///
+    /// * `haystack` - binary to search the pattern in
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::Scanner;
+    ///
+    /// let binary = [0xe8, 0x10, 0x00, 0xe8, 0x20, 0x00];
+    ///
+    /// let scanner = Scanner::new("e8 ?? 00");
+    /// let offsets = scanner
+    ///     .par_find_all(None, &binary)
+    ///     .iter()
+    ///     .map(|result| result.offset())
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(offsets, [0, 3]);
+    /// ```
+    pub fn par_find_all<'a>(
+        &self,
+        preferred_scan_mode: Option<ScanMode>,
+        haystack: &'a [u8],
+    ) -> Vec<Match<'a>> {
+        if haystack.len() <= CHUNK_SIZE || self.0.unpadded_size == 0 {
+            return self
+                .find_all_in_with_mode(preferred_scan_mode, haystack)
+                .collect();
+        }
+
+        chunks(haystack.len(), self.0.unpadded_size)
+            .into_par_iter()
+            .flat_map_iter(|(chunk_start, chunk_end)| {
+                self.find_all_in_with_mode(preferred_scan_mode, &haystack[chunk_start..chunk_end])
+                    .map(move |result| Match::new(haystack, chunk_start + result.start(), &self.0))
+            })
+            .collect()
+    }
+}
+
+/// Split a haystack into chunks of [`CHUNK_SIZE`] match starts
+///
+/// Every chunk overlaps the next one by `pattern_size - 1` bytes, so matches crossing
+/// a chunk boundary are found exactly once, in the chunk they start in.
+fn chunks(haystack_size: usize, pattern_size: usize) -> Vec<(usize, usize)> {
+    (0..haystack_size)
+        .step_by(CHUNK_SIZE)
+        .map(|chunk_start| {
+            let chunk_end = (chunk_start + CHUNK_SIZE + pattern_size - 1).min(haystack_size);
+            (chunk_start, chunk_end)
+        })
+        .collect()
+}
@@ -1,108 +1,500 @@
 //! IDA-style pattern
 
 use crate::aligned_bytes::AlignedBytes;
+use crate::backends::prefilter::RareBytes;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
 /// An IDA-style binary pattern
 pub struct Pattern {
     pub(crate) data: Box<AlignedBytes<32>>,
     pub(crate) mask: Box<AlignedBytes<32>>,
     pub(crate) unpadded_size: usize,
+    pub(crate) result_offset: usize,
+    pub(crate) rare_bytes: Option<RareBytes>,
 }
 
 impl Pattern {
     const ALIGNMENT: usize = 32;
+    const RESULT_MARKER: char = '&';
 
     /// Create a new IDA-style [`Pattern`] instance
     ///
+    /// # Panics
+    ///
+    /// Panics if the pattern is malformed, see [`Pattern::parse`] for a fallible alternative.
+    ///
     /// # Example
     ///
     /// ```
     /// use lightningscanner::pattern::Pattern;
     ///
     /// Pattern::new("48 89 5c 24 ?? 48 89 6c");
+    /// Pattern::new("4? 8b ?5");
     /// ```
     pub fn new(pattern: &str) -> Self {
-        let pattern = pattern.chars().collect::<Vec<_>>();
+        match Self::parse(pattern) {
+            Ok(pattern) => pattern,
+            Err(err) => panic!("invalid pattern {pattern:?}: {err}"),
+        }
+    }
+
+    /// Parse an IDA-style [`Pattern`]
+    ///
+    /// Bytes are written as two hex digits, wildcards as `?` or `??`.
+    /// Either digit of a byte can be replaced with `?` to only match the other nibble, e.g. `4?` or `?5`.
+    /// Tokens may be separated by whitespace.
+    ///
+    /// A single `&` marks the result offset, scan results will point at the byte following it
+    /// instead of the start of the pattern.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::pattern::{Pattern, PatternErrorKind};
+    ///
+    /// assert!(Pattern::parse("48 89 5c 24 ?? 48 89 6c").is_ok());
+    /// assert_eq!(Pattern::parse("48 8b 05 & ?? ?? ?? ??").unwrap().result_offset(), 3);
+    ///
+    /// let err = Pattern::parse("48 8G").err().unwrap();
+    /// assert_eq!(err.kind(), PatternErrorKind::InvalidHexDigit);
+    /// assert_eq!(err.column(), 5);
+    /// assert_eq!(err.token(), "8G");
+    /// ```
+    pub fn parse(pattern: &str) -> Result<Self, PatternError> {
+        let symbols = pattern.char_indices().collect::<Vec<_>>();
 
         let mut data = Vec::new();
         let mut mask = Vec::new();
+        let mut result_offset = None;
 
-        let mut i = 0;
-        while i < pattern.len() {
-            let symbol = pattern[i];
-            let next_symbol = pattern.get(i + 1).copied().unwrap_or('\0');
+        let is_separator = |symbol: char| symbol.is_whitespace() || symbol == Self::RESULT_MARKER;
 
-            i += 1;
+        let error = |i: usize, kind: PatternErrorKind| {
+            let index = symbols.get(i).map_or(pattern.len(), |&(index, _)| index);
+            PatternError::new(pattern, i, index, kind)
+        };
+
+        let mut i = 0;
+        while i < symbols.len() {
+            let (_, symbol) = symbols[i];
+            let next_symbol = symbols.get(i + 1).map(|&(_, symbol)| symbol);
 
             match symbol {
-                ' ' => continue,
+                _ if symbol.is_whitespace() => {
+                    i += 1;
+                }
+                Self::RESULT_MARKER => {
+                    if result_offset.is_some() {
+                        return Err(error(i, PatternErrorKind::DuplicateResultMarker));
+                    }
+
+                    result_offset = Some(data.len());
+                    i += 1;
+                }
                 '?' => {
-                    data.push(0x00);
-                    mask.push(0x00);
+                    match next_symbol {
+                        Some('?') => i += 2,
+                        None => i += 1,
+                        Some(next_symbol) if is_separator(next_symbol) => i += 1,
+                        Some(next_symbol) if next_symbol.is_ascii_hexdigit() => {
+                            data.push(Self::char_to_byte(next_symbol));
+                            mask.push(0x0f);
 
-                    if next_symbol == '?' {
-                        i += 1;
+                            i += 2;
+                            continue;
+                        }
+                        Some(next_symbol) if next_symbol.is_alphanumeric() => {
+                            return Err(error(i + 1, PatternErrorKind::InvalidHexDigit));
+                        }
+                        Some(_) => return Err(error(i + 1, PatternErrorKind::UnsupportedToken)),
                     }
 
-                    continue;
+                    data.push(0x00);
+                    mask.push(0x00);
                 }
-                _ => {
-                    let byte = (Self::char_to_byte(symbol) << 4) | Self::char_to_byte(next_symbol);
+                _ if symbol.is_ascii_hexdigit() => {
+                    let (low, low_mask) = match next_symbol {
+                        Some(next_symbol) if next_symbol.is_ascii_hexdigit() => {
+                            (Self::char_to_byte(next_symbol), 0x0f)
+                        }
+                        Some('?') => (0x00, 0x00),
+                        Some(next_symbol) if next_symbol.is_alphanumeric() => {
+                            return Err(error(i + 1, PatternErrorKind::InvalidHexDigit));
+                        }
+                        None => return Err(error(i, PatternErrorKind::DanglingNibble)),
+                        Some(next_symbol) if is_separator(next_symbol) => {
+                            return Err(error(i, PatternErrorKind::DanglingNibble));
+                        }
+                        Some(_) => return Err(error(i + 1, PatternErrorKind::UnsupportedToken)),
+                    };
 
-                    data.push(byte);
-                    mask.push(0xff);
+                    data.push((Self::char_to_byte(symbol) << 4) | low);
+                    mask.push(0xf0 | low_mask);
 
-                    i += 1;
+                    i += 2;
+                }
+                _ if symbol.is_alphanumeric() => {
+                    return Err(error(i, PatternErrorKind::InvalidHexDigit));
                 }
+                _ => return Err(error(i, PatternErrorKind::UnsupportedToken)),
             }
+        }
+
+        if data.is_empty() {
+            return Err(error(0, PatternErrorKind::EmptyPattern));
+        }
+
+        let pattern = Self::from_parts(data, mask);
+        Ok(pattern.with_result_offset(result_offset.unwrap_or(0)))
+    }
+
+    /// Create a new [`Pattern`] instance based upon a string literal.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::pattern::Pattern;
+    ///
+    /// Pattern::new_string("LocalPlayer");
+    /// ```
+    pub fn new_string(string: &str) -> Self {
+        Self::from_bytes(string.as_bytes())
+    }
+
+    /// Create a new [`Pattern`] instance matching the exact bytes
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::pattern::Pattern;
+    ///
+    /// Pattern::from_bytes(&[0x48, 0x89, 0x5c, 0x24]);
+    /// ```
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self::from_parts(bytes.to_vec(), vec![0xff; bytes.len()])
+    }
+
+    /// Create a new [`Pattern`] instance from bytes and a per-bit mask
+    ///
+    /// A binary byte matches when it equals the pattern byte in every bit set in the mask,
+    /// a mask byte of `0x00` is a full wildcard and `0xff` is an exact match.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `bytes` and `mask` have different lengths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::pattern::Pattern;
+    ///
+    /// // equivalent to "48 8b ?5 ??"
+    /// Pattern::from_bytes_and_mask(&[0x48, 0x8b, 0x05, 0x00], &[0xff, 0xff, 0x0f, 0x00]);
+    /// ```
+    pub fn from_bytes_and_mask(bytes: &[u8], mask: &[u8]) -> Self {
+        assert_eq!(
+            bytes.len(),
+            mask.len(),
+            "pattern bytes and mask must have the same length"
+        );
 
-            if symbol == ' ' {
+        Self::from_parts(bytes.to_vec(), mask.to_vec())
+    }
+
+    /// Parse a code-style [`Pattern`] from a C string literal and a mask
+    ///
+    /// The bytes are written as `\xHH` escapes (`\0` and plain ASCII characters are also accepted),
+    /// the mask contains an `x` for every byte that has to match and a `?` for every wildcard.
+    /// Mask errors report the column inside of the mask.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::pattern::Pattern;
+    ///
+    /// let pattern = Pattern::from_code_style(r"\x48\x89\x5C\x24\x00", "xxxx?").unwrap();
+    ///
+    /// assert_eq!(pattern.to_code_style().1, "xxxx?");
+    /// ```
+    pub fn from_code_style(bytes: &str, mask: &str) -> Result<Self, PatternError> {
+        let symbols = bytes.chars().collect::<Vec<_>>();
+
+        let error = |i: usize, len: usize, kind: PatternErrorKind| {
+            let token = symbols[i..(i + len).min(symbols.len())]
+                .iter()
+                .collect::<String>();
+            PatternError::at(i + 1, token, kind)
+        };
+
+        let mut data = Vec::new();
+
+        let mut i = 0;
+        while i < symbols.len() {
+            let symbol = symbols[i];
+
+            if symbol != '\\' {
+                if !symbol.is_ascii() {
+                    return Err(error(i, 1, PatternErrorKind::UnsupportedToken));
+                }
+
+                data.push(symbol as u8);
+                i += 1;
                 continue;
             }
+
+            match symbols.get(i + 1) {
+                Some('x') => {
+                    for nibble in i + 2..i + 4 {
+                        match symbols.get(nibble) {
+                            Some(symbol) if symbol.is_ascii_hexdigit() => {}
+                            Some(symbol) if symbol.is_alphanumeric() => {
+                                return Err(error(i, 4, PatternErrorKind::InvalidHexDigit));
+                            }
+                            _ => {
+                                return Err(error(i, nibble - i, PatternErrorKind::DanglingNibble))
+                            }
+                        }
+                    }
+
+                    data.push(
+                        (Self::char_to_byte(symbols[i + 2]) << 4)
+                            | Self::char_to_byte(symbols[i + 3]),
+                    );
+                    i += 4;
+                }
+                Some('0') => {
+                    data.push(0x00);
+                    i += 2;
+                }
+                _ => return Err(error(i, 2, PatternErrorKind::UnsupportedToken)),
+            }
         }
 
-        let unpadded_size = data.len();
+        if data.is_empty() {
+            return Err(PatternError::at(1, "", PatternErrorKind::EmptyPattern));
+        }
 
-        let count = f32::ceil(unpadded_size as f32 / Self::ALIGNMENT as f32) as usize;
-        let padding_size = count * Self::ALIGNMENT - unpadded_size;
+        let mask = mask
+            .chars()
+            .enumerate()
+            .map(|(i, symbol)| match symbol {
+                'x' | 'X' => Ok(0xff),
+                '?' => Ok(0x00),
+                _ => Err(PatternError::at(
+                    i + 1,
+                    symbol.to_string(),
+                    PatternErrorKind::UnsupportedToken,
+                )),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
 
-        data.resize(unpadded_size + padding_size, 0);
-        mask.resize(unpadded_size + padding_size, 0);
+        if mask.len() != data.len() {
+            return Err(PatternError::at(
+                mask.len().min(data.len()) + 1,
+                "",
+                PatternErrorKind::MaskLengthMismatch,
+            ));
+        }
 
-        Pattern {
-            data: AlignedBytes::new(&data),
-            mask: AlignedBytes::new(&mask),
-            unpadded_size,
+        Ok(Self::from_parts(data, mask))
+    }
+
+    /// Get the result offset of the pattern
+    ///
+    /// Scan results point this many bytes past the start of the match.
+    pub fn result_offset(&self) -> usize {
+        self.result_offset
+    }
+
+    /// Set the result offset of the pattern
+    ///
+    /// # Panics
+    ///
+    /// Panics if `result_offset` is larger than the pattern size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::pattern::Pattern;
+    ///
+    /// let pattern = Pattern::from_bytes(&[0xe8, 0x10, 0x00, 0x00, 0x00]).with_result_offset(1);
+    ///
+    /// assert_eq!(pattern, Pattern::new("e8 & 10 00 00 00"));
+    /// ```
+    pub fn with_result_offset(mut self, result_offset: usize) -> Self {
+        assert!(
+            result_offset <= self.unpadded_size,
+            "result offset {result_offset} is out of pattern bounds"
+        );
+
+        self.result_offset = result_offset;
+        self
+    }
+
+    /// Format the pattern as a code-style C string literal and mask
+    ///
+    /// Code-style masks can only express whole-byte wildcards,
+    /// bytes with a partial mask are emitted as wildcards.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::pattern::Pattern;
+    ///
+    /// let (bytes, mask) = Pattern::new("48 8b 05 ?? ?? ?? ??").to_code_style();
+    ///
+    /// assert_eq!(bytes, r"\x48\x8B\x05\x00\x00\x00\x00");
+    /// assert_eq!(mask, "xxx????");
+    /// ```
+    pub fn to_code_style(&self) -> (String, String) {
+        let mut bytes = String::with_capacity(self.unpadded_size * 4);
+        let mut mask = String::with_capacity(self.unpadded_size);
+
+        for (byte, byte_mask) in self
+            .data
+            .iter()
+            .zip(self.mask.iter())
+            .take(self.unpadded_size)
+        {
+            if *byte_mask == 0xff {
+                bytes.push_str(&format!("\\x{byte:02X}"));
+                mask.push('x');
+            } else {
+                bytes.push_str("\\x00");
+                mask.push('?');
+            }
         }
+
+        (bytes, mask)
     }
 
-    /// Create a new [`Pattern`] instance based upon a string literal.
-    /// 
+    /// Format the pattern as an x64dbg-style pattern
+    ///
+    /// Bytes are not separated, wildcards are written as `??` and nibble wildcards as `?`.
+    /// x64dbg has no result offset syntax, so the result offset is not included.
+    ///
     /// # Example
-    /// 
+    ///
     /// ```
     /// use lightningscanner::pattern::Pattern;
-    /// 
-    /// Pattern::new_string("LocalPlayer")
+    ///
+    /// let pattern = Pattern::new("48 8b ?5 ??");
+    ///
+    /// assert_eq!(pattern.to_x64dbg_string(), "488B?5??");
     /// ```
-    pub fn new_string(string: &str) -> Self {
-        let bytes = string.as_bytes();
-        
-        let mut data = bytes.to_vec();
-        let mut mask = vec![0xff; bytes.len()];
-        
+    pub fn to_x64dbg_string(&self) -> String {
+        let mut string = String::with_capacity(self.unpadded_size * 2);
+        // formatting into a string never fails
+        let _ = self.write_tokens(&mut string, "", "??", '?', false);
+        string
+    }
+
+    /// Format the pattern as a Cheat Engine AOB pattern
+    ///
+    /// Wildcards are written as `*` and nibble wildcards as `?`.
+    /// The result offset is not included.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use lightningscanner::pattern::Pattern;
+    ///
+    /// let pattern = Pattern::new("48 8b ?5 ??");
+    ///
+    /// assert_eq!(pattern.to_cheat_engine_string(), "48 8B ?5 *");
+    /// ```
+    pub fn to_cheat_engine_string(&self) -> String {
+        let mut string = String::with_capacity(self.unpadded_size * 3);
+        // formatting into a string never fails
+        let _ = self.write_tokens(&mut string, " ", "*", '?', false);
+        string
+    }
+
+    /// Write the pattern bytes as hex tokens
+    ///
+    /// Nibbles which are not fully covered by the mask are written as `nibble_wildcard`,
+    /// bytes with no mask bits at all as `wildcard`.
+    /// The result offset is only written when `result_marker` is set.
+    fn write_tokens(
+        &self,
+        f: &mut impl fmt::Write,
+        separator: &str,
+        wildcard: &str,
+        nibble_wildcard: char,
+        result_marker: bool,
+    ) -> fmt::Result {
+        let has_marker = result_marker && self.result_offset != 0;
+
+        for i in 0..self.unpadded_size {
+            if i != 0 {
+                f.write_str(separator)?;
+            }
+
+            if has_marker && i == self.result_offset {
+                f.write_char(Self::RESULT_MARKER)?;
+                f.write_str(separator)?;
+            }
+
+            let (byte, mask) = (self.data[i], self.mask[i]);
+
+            if mask == 0x00 {
+                f.write_str(wildcard)?;
+                continue;
+            }
+
+            for shift in [4, 0] {
+                if (mask >> shift) & 0xf == 0xf {
+                    write!(f, "{:X}", (byte >> shift) & 0xf)?;
+                } else {
+                    f.write_char(nibble_wildcard)?;
+                }
+            }
+        }
+
+        if has_marker && self.result_offset == self.unpadded_size {
+            f.write_str(separator)?;
+            f.write_char(Self::RESULT_MARKER)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check if the pattern matches the start of `window`
+    pub(crate) fn matches(&self, window: &[u8]) -> bool {
+        window.len() >= self.unpadded_size
+            && window
+                .iter()
+                .zip(self.data.iter().zip(self.mask.iter()))
+                .take(self.unpadded_size)
+                .all(|(byte, (data, mask))| byte & mask == *data)
+    }
+
+    /// Create a [`Pattern`] from unpadded data and mask, padding both to the alignment
+    ///
+    /// Data bits not covered by the mask are cleared, so backends can compare masked binary bytes directly.
+    fn from_parts(mut data: Vec<u8>, mut mask: Vec<u8>) -> Self {
+        debug_assert_eq!(data.len(), mask.len());
+
+        for (byte, mask) in data.iter_mut().zip(&mask) {
+            *byte &= mask;
+        }
+
         let unpadded_size = data.len();
-        
-        let count = f32::ceil(unpadded_size as f32 / Self::ALIGNMENT as f32) as usize;
-        let padding_size = count * Self::ALIGNMENT - unpadded_size;
-        
-        data.resize(unpadded_size + padding_size, 0);
-        mask.resize(unpadded_size + padding_size, 0);
-        
+        let padded_size = unpadded_size.next_multiple_of(Self::ALIGNMENT);
+        let rare_bytes = RareBytes::new(&data, &mask);
+
+        data.resize(padded_size, 0);
+        mask.resize(padded_size, 0);
+
         Pattern {
             data: AlignedBytes::new(&data),
             mask: AlignedBytes::new(&mask),
             unpadded_size,
+            result_offset: 0,
+            rare_bytes,
         }
     }
 
@@ -119,8 +511,195 @@ impl Pattern {
     }
 }
 
+impl Clone for Pattern {
+    fn clone(&self) -> Self {
+        Pattern {
+            data: AlignedBytes::new(&self.data),
+            mask: AlignedBytes::new(&self.mask),
+            unpadded_size: self.unpadded_size,
+            result_offset: self.result_offset,
+            rare_bytes: self.rare_bytes,
+        }
+    }
+}
+
+impl PartialEq for Pattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.data[..self.unpadded_size] == other.data[..other.unpadded_size]
+            && self.mask[..self.unpadded_size] == other.mask[..other.unpadded_size]
+            && self.result_offset == other.result_offset
+    }
+}
+
+impl Eq for Pattern {}
+
+impl Hash for Pattern {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data[..self.unpadded_size].hash(state);
+        self.mask[..self.unpadded_size].hash(state);
+        self.result_offset.hash(state);
+    }
+}
+
+/// Formats the pattern as canonical IDA-style text
+///
+/// Bytes are separated by spaces and every wildcard byte or nibble is written as `?`.
+/// Masks that do not cover a nibble completely are written as a nibble wildcard.
+/// A non-zero result offset is written as an `&` marker.
+///
+/// # Example
+///
+/// ```
+/// use lightningscanner::pattern::Pattern;
+///
+/// let pattern = Pattern::new("48 8b ?? 4? ?5");
+///
+/// assert_eq!(pattern.to_string(), "48 8B ? 4? ?5");
+/// ```
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_tokens(f, " ", "?", '?', true)
+    }
+}
+
+impl fmt::Debug for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        struct HexBytes<'a>(&'a [u8]);
+
+        impl fmt::Debug for HexBytes<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{:02x?}", self.0)
+            }
+        }
+
+        f.debug_struct("Pattern")
+            .field("data", &HexBytes(&self.data[..self.unpadded_size]))
+            .field("mask", &HexBytes(&self.mask[..self.unpadded_size]))
+            .field("unpadded_size", &self.unpadded_size)
+            .field("result_offset", &self.result_offset)
+            .finish()
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = PatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Pattern::parse(s)
+    }
+}
+
+/// Compatibility shim for code written before patterns were validated
+///
+/// # Panics
+///
+/// Panics with the [`PatternError`] message if the pattern is malformed, like [`Pattern::new`].
+/// Use [`Pattern::parse`] or [`str::parse`] to handle malformed patterns.
 impl From<&str> for Pattern {
     fn from(value: &str) -> Self {
         Pattern::new(value)
     }
 }
+
+/// Reason a pattern failed to parse
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PatternErrorKind {
+    /// A byte contains a character that is not a hex digit
+    InvalidHexDigit,
+    /// A byte is missing its second hex digit
+    DanglingNibble,
+    /// The pattern contains no bytes
+    EmptyPattern,
+    /// The pattern contains a token that is not supported
+    UnsupportedToken,
+    /// The mask length differs from the number of pattern bytes
+    MaskLengthMismatch,
+    /// The pattern contains more than one result-offset marker
+    DuplicateResultMarker,
+}
+
+impl fmt::Display for PatternErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            PatternErrorKind::InvalidHexDigit => "invalid hex digit",
+            PatternErrorKind::DanglingNibble => "dangling nibble",
+            PatternErrorKind::EmptyPattern => "empty pattern",
+            PatternErrorKind::UnsupportedToken => "unsupported token",
+            PatternErrorKind::MaskLengthMismatch => "mask length mismatch",
+            PatternErrorKind::DuplicateResultMarker => "duplicate result marker",
+        };
+
+        f.write_str(description)
+    }
+}
+
+/// Pattern parsing error
+///
+/// Returned by [`Pattern::parse`] and [`Pattern::from_code_style`] when a pattern is malformed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PatternError {
+    column: usize,
+    token: String,
+    kind: PatternErrorKind,
+}
+
+impl PatternError {
+    fn new(pattern: &str, char_index: usize, index: usize, kind: PatternErrorKind) -> Self {
+        let start = pattern[..index]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| c.is_whitespace())
+            .map_or(0, |(start, c)| start + c.len_utf8());
+        let end = pattern[index..]
+            .find(char::is_whitespace)
+            .map_or(pattern.len(), |end| index + end);
+
+        PatternError {
+            column: char_index + 1,
+            token: pattern[start..end].to_string(),
+            kind,
+        }
+    }
+
+    fn at(column: usize, token: impl Into<String>, kind: PatternErrorKind) -> Self {
+        PatternError {
+            column,
+            token: token.into(),
+            kind,
+        }
+    }
+
+    /// Get the 1-based column of the offending character
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Get the token containing the offending character
+    pub fn token(&self) -> &str {
+        &self.token
+    }
+
+    /// Get the reason the pattern failed to parse
+    pub fn kind(&self) -> PatternErrorKind {
+        self.kind
+    }
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            PatternErrorKind::EmptyPattern => write!(f, "{}", self.kind),
+            PatternErrorKind::MaskLengthMismatch => {
+                write!(f, "{} at column {}", self.kind, self.column)
+            }
+            _ => write!(
+                f,
+                "{} at column {} (`{}`)",
+                self.kind, self.column, self.token
+            ),
+        }
+    }
+}
+
+impl Error for PatternError {}
@@ -0,0 +1,334 @@
+//! PE/COFF file parsing and section-restricted scanning
+
+use crate::reader::Reader;
+use crate::{Match, Scanner};
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+const IMAGE_SCN_CNT_CODE: u32 = 0x0000_0020;
+const IMAGE_SCN_MEM_EXECUTE: u32 = 0x2000_0000;
+const IMAGE_SCN_MEM_READ: u32 = 0x4000_0000;
+const IMAGE_SCN_MEM_WRITE: u32 = 0x8000_0000;
+
+/// Error returned when parsing a PE file fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PeError {
+    /// The file does not start with the `MZ` DOS header magic
+    BadDosMagic,
+    /// The NT headers do not start with the `PE\0\0` signature
+    BadNtSignature,
+    /// The optional header is neither PE32 nor PE32+
+    UnsupportedOptionalHeader(u16),
+    /// A header or section lies outside of the file
+    Truncated,
+}
+
+impl fmt::Display for PeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PeError::BadDosMagic => write!(f, "missing DOS header magic"),
+            PeError::BadNtSignature => write!(f, "missing PE signature"),
+            PeError::UnsupportedOptionalHeader(magic) => {
+                write!(f, "unsupported optional header magic {magic:#x}")
+            }
+            PeError::Truncated => write!(f, "truncated PE file"),
+        }
+    }
+}
+
+impl Error for PeError {}
+
+/// A section of a PE file, as described by its section table entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    name: String,
+    virtual_size: u32,
+    rva: u32,
+    raw_size: u32,
+    raw_offset: u32,
+    characteristics: u32,
+}
+
+impl Section {
+    /// Get the name of the section, such as `.text`
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the relative virtual address of the section
+    pub fn rva(&self) -> u32 {
+        self.rva
+    }
+
+    /// Get the size of the section in memory
+    pub fn virtual_size(&self) -> u32 {
+        self.virtual_size
+    }
+
+    /// Get the section characteristics flags
+    pub fn characteristics(&self) -> u32 {
+        self.characteristics
+    }
+
+    /// Get the file offsets of the section data
+    ///
+    /// File alignment padding past the virtual size of the section is not included.
+    pub fn file_range(&self) -> Range<usize> {
+        let size = match self.virtual_size {
+            0 => self.raw_size,
+            virtual_size => self.raw_size.min(virtual_size),
+        };
+
+        // saturates on 32-bit targets, such a range is rejected by `Pe::parse`
+        let start = self.raw_offset as usize;
+        start..start.saturating_add(size as usize)
+    }
+
+    /// Check if the section can be read
+    pub fn is_readable(&self) -> bool {
+        self.characteristics & IMAGE_SCN_MEM_READ != 0
+    }
+
+    /// Check if the section can be written to
+    pub fn is_writable(&self) -> bool {
+        self.characteristics & IMAGE_SCN_MEM_WRITE != 0
+    }
+
+    /// Check if the section contains executable code
+    pub fn is_executable(&self) -> bool {
+        self.characteristics & (IMAGE_SCN_MEM_EXECUTE | IMAGE_SCN_CNT_CODE) != 0
+    }
+}
+
+/// A parsed PE32 or PE32+ file
+///
+/// Addresses are reported relative to the image base (RVA) or as virtual addresses (VA)
+/// using the preferred image base from the optional header.
+///
+/// # Example
+///
+/// ```no_run
+/// use lightningscanner::pe::Pe;
+/// use lightningscanner::Scanner;
+///
+/// let data = std::fs::read("game.exe").unwrap();
+/// let pe = Pe::parse(&data).unwrap();
+///
+/// let scanner = Scanner::new("48 89 5c 24 ?? 48 89 6c");
+/// if let Some(result) = pe.find_in_section(&scanner, ".text") {
+///     println!("found at {:#x}", pe.offset_to_va(result.offset()).unwrap());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Pe<'a> {
+    data: &'a [u8],
+    is_64: bool,
+    machine: u16,
+    image_base: u64,
+    entry_point: u32,
+    sections: Vec<Section>,
+}
+
+impl<'a> Pe<'a> {
+    /// Parse the headers and section table of a PE file
+    pub fn parse(data: &'a [u8]) -> Result<Self, PeError> {
+        let reader = Reader::new(data, false);
+
+        if !data.starts_with(b"MZ") {
+            return Err(PeError::BadDosMagic);
+        }
+
+        let nt_headers = reader.u32(0x3c).ok_or(PeError::Truncated)? as usize;
+        if reader.bytes(nt_headers, 4).ok_or(PeError::Truncated)? != b"PE\0\0" {
+            return Err(PeError::BadNtSignature);
+        }
+
+        let optional_header = nt_headers + 0x18;
+        let is_64 = match reader.u16(optional_header).ok_or(PeError::Truncated)? {
+            0x10b => false,
+            0x20b => true,
+            magic => return Err(PeError::UnsupportedOptionalHeader(magic)),
+        };
+
+        let pe = Self::parse_headers(data, nt_headers, is_64).ok_or(PeError::Truncated)?;
+
+        let in_bounds = |range: Range<usize>| range.end <= data.len();
+        if !pe.sections.iter().map(Section::file_range).all(in_bounds) {
+            return Err(PeError::Truncated);
+        }
+
+        Ok(pe)
+    }
+
+    fn parse_headers(data: &'a [u8], nt_headers: usize, is_64: bool) -> Option<Self> {
+        let reader = Reader::new(data, false);
+
+        let file_header = nt_headers + 0x4;
+        let machine = reader.u16(file_header)?;
+        let section_count = reader.u16(file_header + 0x2)? as usize;
+        let optional_header_size = reader.u16(file_header + 0x10)? as usize;
+
+        let optional_header = nt_headers + 0x18;
+        let entry_point = reader.u32(optional_header + 0x10)?;
+        let image_base = if is_64 {
+            reader.u64(optional_header + 0x18)?
+        } else {
+            reader.u32(optional_header + 0x1c)? as u64
+        };
+
+        let section_table = optional_header + optional_header_size;
+        let mut sections = Vec::new();
+        for index in 0..section_count {
+            let entry = section_table + index * 0x28;
+
+            let name = reader.bytes(entry, 8)?;
+            let name_len = name.iter().position(|&byte| byte == 0).unwrap_or(8);
+
+            sections.push(Section {
+                name: String::from_utf8_lossy(&name[..name_len]).into_owned(),
+                virtual_size: reader.u32(entry + 0x8)?,
+                rva: reader.u32(entry + 0xc)?,
+                raw_size: reader.u32(entry + 0x10)?,
+                raw_offset: reader.u32(entry + 0x14)?,
+                characteristics: reader.u32(entry + 0x24)?,
+            });
+        }
+
+        Some(Pe {
+            data,
+            is_64,
+            machine,
+            image_base,
+            entry_point,
+            sections,
+        })
+    }
+
+    /// Get the raw file data
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Check if the file is a PE32+ (64-bit) file
+    pub fn is_64(&self) -> bool {
+        self.is_64
+    }
+
+    /// Get the target architecture (`Machine` of the file header)
+    pub fn machine(&self) -> u16 {
+        self.machine
+    }
+
+    /// Get the preferred image base from the optional header
+    pub fn image_base(&self) -> u64 {
+        self.image_base
+    }
+
+    /// Get the relative virtual address of the entry point
+    pub fn entry_point(&self) -> u32 {
+        self.entry_point
+    }
+
+    /// Get all sections of the file
+    pub fn sections(&self) -> &[Section] {
+        &self.sections
+    }
+
+    /// Get the first section with the given name
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|section| section.name == name)
+    }
+
+    /// Get the data of a section
+    pub fn section_data(&self, section: &Section) -> &'a [u8] {
+        &self.data[section.file_range()]
+    }
+
+    /// Convert a file offset to a relative virtual address
+    ///
+    /// Returns `None` if the offset is not part of a section,
+    /// or the address does not fit into 32 bits in a malformed file.
+    pub fn offset_to_rva(&self, offset: usize) -> Option<u32> {
+        let section = self
+            .sections
+            .iter()
+            .find(|section| section.file_range().contains(&offset))?;
+
+        let section_offset = u32::try_from(offset - section.raw_offset as usize).ok()?;
+        section.rva.checked_add(section_offset)
+    }
+
+    /// Convert a file offset to a virtual address using the preferred image base
+    ///
+    /// Returns `None` if the offset is not part of a section.
+    pub fn offset_to_va(&self, offset: usize) -> Option<u64> {
+        self.offset_to_rva(offset).map(|rva| self.rva_to_va(rva))
+    }
+
+    /// Convert a relative virtual address to a file offset
+    ///
+    /// Returns `None` if the address is not backed by file data of a section.
+    pub fn rva_to_offset(&self, rva: u32) -> Option<usize> {
+        self.sections.iter().find_map(|section| {
+            let range = section.file_range();
+            let offset = rva.checked_sub(section.rva)? as usize;
+
+            (offset < range.len()).then_some(range.start + offset)
+        })
+    }
+
+    /// Convert a relative virtual address to a virtual address using the preferred image base
+    pub fn rva_to_va(&self, rva: u32) -> u64 {
+        self.image_base.wrapping_add(rva as u64)
+    }
+
+    /// Convert a virtual address to a relative virtual address using the preferred image base
+    ///
+    /// Returns `None` if the address lies below the image base or too far above it.
+    pub fn va_to_rva(&self, va: u64) -> Option<u32> {
+        va.checked_sub(self.image_base)?.try_into().ok()
+    }
+
+    /// Find the first occurence of the pattern in the section with the given name
+    ///
+    /// The match offset is a file offset, see [`Pe::offset_to_rva`] and [`Pe::offset_to_va`].
+    pub fn find_in_section(&self, scanner: &Scanner, name: &str) -> Option<Match<'a>> {
+        let range = self.section(name)?.file_range();
+        scanner.find_in_range(self.data, range)
+    }
+
+    /// Find every occurence of the pattern in the section with the given name
+    ///
+    /// The match offsets are file offsets, see [`Pe::offset_to_rva`] and [`Pe::offset_to_va`].
+    pub fn find_all_in_section(&self, scanner: &Scanner, name: &str) -> Vec<Match<'a>> {
+        match self.section(name) {
+            Some(section) => scanner
+                .find_all_in_range(self.data, section.file_range())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Find the first occurence of the pattern in the executable sections
+    ///
+    /// The match offset is a file offset, see [`Pe::offset_to_rva`] and [`Pe::offset_to_va`].
+    pub fn find_in_executable_sections(&self, scanner: &Scanner) -> Option<Match<'a>> {
+        self.sections
+            .iter()
+            .filter(|section| section.is_executable())
+            .find_map(|section| scanner.find_in_range(self.data, section.file_range()))
+    }
+
+    /// Find every occurence of the pattern in the executable sections
+    ///
+    /// The match offsets are file offsets, see [`Pe::offset_to_rva`] and [`Pe::offset_to_va`].
+    pub fn find_all_in_executable_sections(&self, scanner: &Scanner) -> Vec<Match<'a>> {
+        self.sections
+            .iter()
+            .filter(|section| section.is_executable())
+            .flat_map(|section| scanner.find_all_in_range(self.data, section.file_range()))
+            .collect()
+    }
+}
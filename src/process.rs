@@ -0,0 +1,333 @@
+//! Scanning of the memory of running processes on Linux
+
+use crate::{MultiScanner, Scanner};
+use std::fs::{self, File};
+use std::io;
+use std::ops::Range;
+use std::os::unix::fs::FileExt;
+use std::path::Path;
+
+/// Number of bytes read from a process at once
+const CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+/// A mapped memory region of a process, as listed in `/proc/<pid>/maps`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    range: Range<usize>,
+    readable: bool,
+    writable: bool,
+    executable: bool,
+    shared: bool,
+    offset: u64,
+    name: String,
+}
+
+impl Region {
+    /// Parse a line of `/proc/<pid>/maps`
+    fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(6, ' ');
+
+        let (start, end) = fields.next()?.split_once('-')?;
+        let permissions = fields.next()?.as_bytes();
+        let offset = fields.next()?;
+        let _device = fields.next()?;
+        let _inode = fields.next()?;
+        let name = fields.next().unwrap_or_default().trim_start();
+
+        if permissions.len() != 4 {
+            return None;
+        }
+
+        Some(Region {
+            range: usize::from_str_radix(start, 16).ok()?..usize::from_str_radix(end, 16).ok()?,
+            readable: permissions[0] == b'r',
+            writable: permissions[1] == b'w',
+            executable: permissions[2] == b'x',
+            shared: permissions[3] == b's',
+            offset: u64::from_str_radix(offset, 16).ok()?,
+            name: name.to_string(),
+        })
+    }
+
+    /// Get the start address of the region
+    pub fn start(&self) -> usize {
+        self.range.start
+    }
+
+    /// Get the end address of the region, exclusive
+    pub fn end(&self) -> usize {
+        self.range.end
+    }
+
+    /// Get the address range of the region
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Get the size of the region in bytes
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    /// Check if the region is empty
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    /// Check if the region can be read
+    pub fn is_readable(&self) -> bool {
+        self.readable
+    }
+
+    /// Check if the region can be written to
+    pub fn is_writable(&self) -> bool {
+        self.writable
+    }
+
+    /// Check if the region can be executed
+    pub fn is_executable(&self) -> bool {
+        self.executable
+    }
+
+    /// Check if the region is shared with other processes
+    pub fn is_shared(&self) -> bool {
+        self.shared
+    }
+
+    /// Get the offset of the region in the mapped file
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    /// Get the name of the region, such as a file path or `[heap]`, empty for anonymous mappings
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the path of the mapped file, if the region maps a file
+    pub fn path(&self) -> Option<&Path> {
+        self.name.starts_with('/').then(|| Path::new(&self.name))
+    }
+}
+
+/// A running process whose memory can be scanned
+///
+/// Memory is read with `process_vm_readv`, falling back to `/proc/<pid>/mem` if the system call
+/// is not available. Reading the memory of another process requires ptrace access to it.
+///
+/// # Example
+///
+/// ```
+/// use lightningscanner::process::Process;
+/// use lightningscanner::Scanner;
+///
+/// static DATA: [u8; 8] = [0x48, 0x89, 0x5c, 0x24, 0x08, 0x48, 0x89, 0x6c];
+///
+/// let process = Process::current();
+/// let scanner = Scanner::new("48 89 5c 24 ?? 48 89 6c");
+///
+/// let addresses = process.find_all(&scanner, |region| !region.is_writable()).unwrap();
+/// assert!(addresses.contains(&(DATA.as_ptr() as usize)));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Process {
+    pid: u32,
+}
+
+impl Process {
+    /// Create a new [`Process`] instance for the process with the given id
+    pub fn new(pid: u32) -> Self {
+        Process { pid }
+    }
+
+    /// Create a new [`Process`] instance for the current process
+    pub fn current() -> Self {
+        Process::new(std::process::id())
+    }
+
+    /// Get the process id
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+
+    /// List the mapped memory regions of the process
+    pub fn regions(&self) -> io::Result<Vec<Region>> {
+        let maps = fs::read_to_string(format!("/proc/{}/maps", self.pid))?;
+
+        maps.lines()
+            .map(|line| {
+                Region::parse(line).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("malformed memory map entry {line:?}"),
+                    )
+                })
+            })
+            .collect()
+    }
+
+    /// Read process memory at `address` into `buffer`, returning the number of bytes read
+    ///
+    /// Fewer bytes than requested are read if the memory range runs into an unmapped page.
+    pub fn read(&self, address: usize, buffer: &mut [u8]) -> io::Result<usize> {
+        let local = libc::iovec {
+            iov_base: buffer.as_mut_ptr() as *mut _,
+            iov_len: buffer.len(),
+        };
+        let remote = libc::iovec {
+            iov_base: address as *mut _,
+            iov_len: buffer.len(),
+        };
+
+        // SAFETY: the local iovec describes `buffer`, which is valid for writes of its length,
+        // the remote iovec is only read by the kernel
+        let read =
+            unsafe { libc::process_vm_readv(self.pid as libc::pid_t, &local, 1, &remote, 1, 0) };
+        if read >= 0 {
+            return Ok(read as usize);
+        }
+
+        let err = io::Error::last_os_error();
+        match err.raw_os_error() {
+            Some(libc::ENOSYS | libc::EPERM) => self.read_mem(address, buffer),
+            _ => Err(err),
+        }
+    }
+
+    /// Read process memory through `/proc/<pid>/mem`
+    fn read_mem(&self, address: usize, buffer: &mut [u8]) -> io::Result<usize> {
+        let mem = File::open(format!("/proc/{}/mem", self.pid))?;
+
+        loop {
+            match mem.read_at(buffer, address as u64) {
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                result => return result,
+            }
+        }
+    }
+
+    /// Find the first occurence of the pattern in the readable regions accepted by `filter`
+    ///
+    /// Regions are scanned in ascending address order, returns the remote address of the match,
+    /// including the result offset of the pattern. Unreadable regions are skipped,
+    /// other read errors, such as missing permissions, are returned.
+    pub fn find(
+        &self,
+        scanner: &Scanner,
+        filter: impl FnMut(&Region) -> bool,
+    ) -> io::Result<Option<usize>> {
+        let mut result = None;
+
+        self.scan(scanner.0.unpadded_size, filter, |address, chunk| {
+            result = scanner
+                .find_in(chunk)
+                .map(|result| address + result.offset());
+
+            result.is_none()
+        })?;
+
+        Ok(result)
+    }
+
+    /// Find every occurence of the pattern in the readable regions accepted by `filter`
+    ///
+    /// Returns the remote addresses of the matches in ascending order, including the result
+    /// offset of the pattern. Unreadable regions are skipped, other read errors, such as missing
+    /// permissions, are returned.
+    pub fn find_all(
+        &self,
+        scanner: &Scanner,
+        filter: impl FnMut(&Region) -> bool,
+    ) -> io::Result<Vec<usize>> {
+        let mut results = Vec::new();
+
+        self.scan(scanner.0.unpadded_size, filter, |address, chunk| {
+            results.extend(
+                scanner
+                    .find_all_in(chunk)
+                    .map(|result| address + result.offset()),
+            );
+
+            true
+        })?;
+
+        Ok(results)
+    }
+
+    /// Find the first occurence of every pattern in the readable regions accepted by `filter`
+    ///
+    /// Returns the remote address of every match in the order the patterns were added,
+    /// including the result offset of the pattern. Unreadable regions are skipped,
+    /// other read errors, such as missing permissions, are returned.
+    pub fn find_multi(
+        &self,
+        scanner: &MultiScanner,
+        filter: impl FnMut(&Region) -> bool,
+    ) -> io::Result<Vec<Option<usize>>> {
+        let mut results = vec![None; scanner.len()];
+
+        self.scan(scanner.max_pattern_size(), filter, |address, chunk| {
+            for (result, found) in results.iter_mut().zip(scanner.find_in(chunk)) {
+                if result.is_none() {
+                    *result = found.map(|found| address + found.offset());
+                }
+            }
+
+            results.iter().any(Option::is_none)
+        })?;
+
+        Ok(results)
+    }
+
+    /// Read the readable regions accepted by `filter` in chunks and pass them to `scan_chunk`
+    ///
+    /// Consecutive chunks of a region overlap by `pattern_size - 1` bytes, so matches crossing
+    /// chunk boundaries are found. Scanning stops once `scan_chunk` returns `false`.
+    ///
+    /// A region stops being read at the first address that cannot be accessed, any other
+    /// read error, such as denied access to the process, ends the scan.
+    fn scan(
+        &self,
+        pattern_size: usize,
+        mut filter: impl FnMut(&Region) -> bool,
+        mut scan_chunk: impl FnMut(usize, &[u8]) -> bool,
+    ) -> io::Result<()> {
+        let overlap = pattern_size.saturating_sub(1);
+        let mut buffer = Vec::new();
+
+        for region in self.regions()? {
+            if !region.is_readable() || !filter(&region) {
+                continue;
+            }
+
+            let mut address = region.start();
+            while address < region.end() {
+                let chunk_size = (region.end() - address).min(CHUNK_SIZE + overlap);
+                buffer.resize(chunk_size, 0);
+
+                let read = match self.read(address, &mut buffer) {
+                    Ok(read) => read,
+                    // the region was unmapped since the memory map was read, or cannot be
+                    // accessed through another process at all, such as `[vvar]`
+                    Err(err) if matches!(err.raw_os_error(), Some(libc::EFAULT | libc::EIO)) => {
+                        break
+                    }
+                    Err(err) => return Err(err),
+                };
+
+                if !scan_chunk(address, &buffer[..read]) {
+                    return Ok(());
+                }
+
+                // the rest of the region is unreadable, or the whole region has been scanned
+                if read < chunk_size || address + read == region.end() {
+                    break;
+                }
+
+                address += CHUNK_SIZE;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+//! Bounds-checked reading of integers from executable file headers
+
+/// Reader of fixed-endianness integers at absolute offsets of a byte slice
+///
+/// Every read returns `None` instead of panicking if it runs past the end of the data.
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct Reader<'a> {
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8], big_endian: bool) -> Self {
+        Reader { data, big_endian }
+    }
+
+    pub fn bytes(&self, offset: usize, len: usize) -> Option<&'a [u8]> {
+        self.data.get(offset..offset.checked_add(len)?)
+    }
+
+    pub fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes = self.bytes(offset, 2)?.try_into().ok()?;
+
+        Some(if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    pub fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes = self.bytes(offset, 4)?.try_into().ok()?;
+
+        Some(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    pub fn u64(&self, offset: usize) -> Option<u64> {
+        let bytes = self.bytes(offset, 8)?.try_into().ok()?;
+
+        Some(if self.big_endian {
+            u64::from_be_bytes(bytes)
+        } else {
+            u64::from_le_bytes(bytes)
+        })
+    }
+
+    /// Read a 32-bit or 64-bit word, depending on the file class
+    pub fn word(&self, offset: usize, is_64: bool) -> Option<u64> {
+        if is_64 {
+            self.u64(offset)
+        } else {
+            self.u32(offset).map(u64::from)
+        }
+    }
+
+    /// Read a nul-terminated string starting at `offset`
+    pub fn c_str(&self, offset: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(offset..)?;
+        let len = bytes.iter().position(|&byte| byte == 0)?;
+
+        Some(&bytes[..len])
+    }
+}
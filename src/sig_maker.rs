@@ -0,0 +1,258 @@
+//! Generation of unique patterns for a location in an x86-64 image
+
+use crate::pattern::Pattern;
+use crate::{x86, Scanner};
+use std::error::Error;
+use std::fmt;
+
+/// Error returned when generating a pattern fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SigMakerError {
+    /// The target offset is out of image bounds
+    OutOfBounds,
+    /// No pattern within the maximum length matches only the target
+    NotUnique,
+}
+
+impl fmt::Display for SigMakerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SigMakerError::OutOfBounds => f.write_str("target offset is out of image bounds"),
+            SigMakerError::NotUnique => f.write_str("no unique pattern within the maximum length"),
+        }
+    }
+}
+
+impl Error for SigMakerError {}
+
+/// Generator of the shortest pattern that occurs exactly once in an image
+///
+/// Patterns are grown byte by byte from the target, which has to be the start of an instruction.
+/// The bytes of every instruction that depend on where code and data are placed, such as branch
+/// displacements, RIP-relative displacements and 64-bit immediate addresses, are wildcarded,
+/// so the pattern keeps matching after the image is rebuilt.
+///
+/// Uniqueness is checked against the whole image, pass the data of a single section
+/// if the pattern will only be scanned for in that section.
+///
+/// # Example
+///
+/// ```
+/// use lightningscanner::pattern::Pattern;
+/// use lightningscanner::sig_maker::SigMaker;
+///
+/// let image = [
+///     0x48, 0x8b, 0x05, 0x10, 0x00, 0x00, 0x00, 0xc3, // mov rax, [rip + 0x10]; ret
+///     0x48, 0x8b, 0x05, 0x20, 0x00, 0x00, 0x00, 0x90, // mov rax, [rip + 0x20]; nop
+/// ];
+///
+/// let pattern = SigMaker::new(&image).make(0).unwrap();
+///
+/// assert_eq!(pattern, Pattern::new("48 8b 05 ?? ?? ?? ?? c3"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SigMaker<'a> {
+    image: &'a [u8],
+    max_len: usize,
+    max_anchor_distance: usize,
+}
+
+impl<'a> SigMaker<'a> {
+    const DEFAULT_MAX_LEN: usize = 64;
+    const DEFAULT_MAX_ANCHOR_DISTANCE: usize = 64;
+
+    /// Number of non-wildcard bytes the initial candidate scan is done with
+    const SEED_LEN: usize = 4;
+
+    /// Create a new [`SigMaker`] instance for the image
+    pub fn new(image: &'a [u8]) -> Self {
+        SigMaker {
+            image,
+            max_len: Self::DEFAULT_MAX_LEN,
+            max_anchor_distance: Self::DEFAULT_MAX_ANCHOR_DISTANCE,
+        }
+    }
+
+    /// Set the maximum length of generated patterns
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_len` is zero.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        assert!(max_len > 0, "maximum pattern length must not be zero");
+
+        self.max_len = max_len;
+        self
+    }
+
+    /// Set how many bytes before the target [`SigMaker::make_anchored`] looks for instructions
+    pub fn with_max_anchor_distance(mut self, max_anchor_distance: usize) -> Self {
+        self.max_anchor_distance = max_anchor_distance;
+        self
+    }
+
+    /// Get the image patterns are generated for
+    pub fn image(&self) -> &'a [u8] {
+        self.image
+    }
+
+    /// Generate the shortest unique pattern starting at `offset`
+    pub fn make(&self, offset: usize) -> Result<Pattern, SigMakerError> {
+        if offset >= self.image.len() {
+            return Err(SigMakerError::OutOfBounds);
+        }
+
+        self.grow(offset, 1).ok_or(SigMakerError::NotUnique)
+    }
+
+    /// Generate the shortest unique pattern starting at an instruction before `offset`
+    ///
+    /// The result offset of the pattern points at `offset`, so scan results point at the target.
+    /// Useful when the bytes at the target are too common, such as at the end of a function.
+    ///
+    /// Instructions before the target are found by decoding forward from every preceding byte,
+    /// a start is used if decoding from it arrives exactly at the target.
+    pub fn make_anchored(&self, offset: usize) -> Result<Pattern, SigMakerError> {
+        if offset >= self.image.len() {
+            return Err(SigMakerError::OutOfBounds);
+        }
+
+        let mut shortest: Option<Pattern> = None;
+
+        for distance in 1..=self.max_anchor_distance.min(offset) {
+            // patterns anchored further away cover at least `distance + 1` bytes
+            if shortest
+                .as_ref()
+                .is_some_and(|shortest| shortest.unpadded_size <= distance + 1)
+            {
+                break;
+            }
+
+            let start = offset - distance;
+            if !self.decodes_to(start, offset) {
+                continue;
+            }
+
+            if let Some(pattern) = self.grow(start, distance + 1) {
+                let is_shorter = match &shortest {
+                    Some(shortest) => pattern.unpadded_size < shortest.unpadded_size,
+                    None => true,
+                };
+
+                if is_shorter {
+                    shortest = Some(pattern.with_result_offset(distance));
+                }
+            }
+        }
+
+        shortest.ok_or(SigMakerError::NotUnique)
+    }
+
+    /// Check if decoding instructions from `start` arrives exactly at `target`
+    fn decodes_to(&self, start: usize, target: usize) -> bool {
+        let mut position = start;
+
+        while position < target {
+            match x86::decode_length(|i| self.image.get(position + i).copied()) {
+                Some(instruction) => position += instruction.len,
+                None => return false,
+            }
+        }
+
+        position == target
+    }
+
+    /// Get the bytes and mask of the longest possible pattern starting at `start`
+    ///
+    /// Bytes that are not part of a decodable instruction are matched exactly.
+    fn template(&self, start: usize) -> (Vec<u8>, Vec<u8>) {
+        let end = self.image.len().min(start.saturating_add(self.max_len));
+        let bytes = self.image[start..end].to_vec();
+        let mut mask = vec![0xff; bytes.len()];
+
+        let mut position = 0;
+        while position < bytes.len() {
+            let instruction = x86::decode_length(|i| self.image.get(start + position + i).copied());
+
+            match instruction {
+                Some(instruction) => {
+                    if let Some(volatile) = instruction.volatile {
+                        let start = (position + volatile.start).min(mask.len());
+                        let end = (position + volatile.end).min(mask.len());
+                        mask[start..end].fill(0x00);
+                    }
+
+                    position += instruction.len;
+                }
+                None => position += 1,
+            }
+        }
+
+        (bytes, mask)
+    }
+
+    /// Grow a pattern from `start` until it matches only at `start`
+    ///
+    /// The pattern covers at least `min_len` bytes and never ends in a wildcard.
+    fn grow(&self, start: usize, min_len: usize) -> Option<Pattern> {
+        let (bytes, mask) = self.template(start);
+        if bytes.len() < min_len {
+            return None;
+        }
+
+        // scan for a short seed once, then narrow the candidates down byte by byte
+        let mut len = mask
+            .iter()
+            .enumerate()
+            .filter(|(_, mask)| **mask != 0x00)
+            .nth(Self::SEED_LEN - 1)
+            .map_or(bytes.len(), |(i, _)| i + 1);
+
+        if mask[..len].iter().all(|mask| *mask == 0x00) {
+            return None;
+        }
+
+        let prefix = |len: usize| Pattern::from_bytes_and_mask(&bytes[..len], &mask[..len]);
+
+        let mut candidates = Scanner::from(prefix(len))
+            .find_all_in(self.image)
+            .map(|result| result.start())
+            .collect::<Vec<_>>();
+
+        // the seed may already be unique, then a shorter prefix could be too
+        if candidates.len() == 1 {
+            let shorter = (min_len..len)
+                .filter(|len| mask[len - 1] != 0x00)
+                .find(|len| {
+                    Scanner::from(prefix(*len))
+                        .find_all_in(self.image)
+                        .nth(1)
+                        .is_none()
+                });
+
+            if let Some(len) = shorter {
+                return Some(prefix(len));
+            }
+        }
+
+        loop {
+            if candidates.len() == 1 && len >= min_len && mask[len - 1] != 0x00 {
+                return Some(prefix(len));
+            }
+
+            if len == bytes.len() {
+                return None;
+            }
+
+            let (byte, byte_mask) = (bytes[len], mask[len]);
+            len += 1;
+
+            candidates.retain(|candidate| {
+                self.image
+                    .get(candidate + len - 1)
+                    .is_some_and(|candidate_byte| candidate_byte & byte_mask == byte & byte_mask)
+            });
+        }
+    }
+}
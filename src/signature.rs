@@ -0,0 +1,714 @@
+//! Signature files, named patterns that are resolved to addresses
+//!
+//! A signature file lists patterns by name, together with where to scan for them
+//! and how to turn a match into the address of interest.
+//! Both TOML and JSON files are supported:
+//!
+//! ```toml
+//! [[signature]]
+//! name = "GWorld"
+//! pattern = "48 8b 05 ?? ?? ?? ?? 48 85 c0 74 ??"
+//! section = ".text"
+//! steps = [{ op = "rel32", disp_offset = 3, instr_len = 7 }, { op = "deref" }]
+//!
+//! [[signature]]
+//! name = "ProcessEvent"
+//! pattern = "40 55 56 57 41 54 41 55 41 56 41 57"
+//! module = "game.exe"
+//! ```
+//!
+//! ```json
+//! {
+//!     "signature": [
+//!         {
+//!             "name": "ProcessEvent",
+//!             "pattern": "e8 & ?? ?? ?? ?? 48 8b 5c 24",
+//!             "steps": [{ "op": "add", "offset": -1 }, { "op": "rel32", "disp_offset": 1, "instr_len": 5 }]
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! Every entry is resolved by finding its pattern in an [`Image`], starting from the result
+//! offset of the pattern, and then applying its [`Step`]s in order.
+
+use crate::pattern::{Pattern, PatternError};
+use crate::Scanner;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::mem;
+use std::path::Path;
+
+/// Error returned when loading a signature file fails
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SignatureError {
+    /// The signature file could not be read
+    Io(io::Error),
+    /// The file extension is neither `.toml` nor `.json`
+    UnknownFormat,
+    /// The TOML document is malformed
+    Toml(toml::de::Error),
+    /// The JSON document is malformed
+    Json(serde_json::Error),
+    /// The pattern of the named signature is malformed
+    Pattern {
+        /// Name of the signature
+        name: String,
+        /// Reason the pattern failed to parse
+        error: PatternError,
+    },
+    /// The result offset of the named signature is larger than its pattern
+    ResultOffsetOutOfBounds(String),
+    /// More than one signature has this name
+    DuplicateName(String),
+}
+
+impl fmt::Display for SignatureError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureError::Io(err) => write!(f, "failed to read signature file: {err}"),
+            SignatureError::UnknownFormat => f.write_str("unknown signature file format"),
+            SignatureError::Toml(err) => write!(f, "malformed TOML signature file: {err}"),
+            SignatureError::Json(err) => write!(f, "malformed JSON signature file: {err}"),
+            SignatureError::Pattern { name, error } => {
+                write!(f, "malformed pattern in signature {name:?}: {error}")
+            }
+            SignatureError::ResultOffsetOutOfBounds(name) => {
+                write!(
+                    f,
+                    "result offset of signature {name:?} is out of pattern bounds"
+                )
+            }
+            SignatureError::DuplicateName(name) => write!(f, "duplicate signature name {name:?}"),
+        }
+    }
+}
+
+impl Error for SignatureError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SignatureError::Io(err) => Some(err),
+            SignatureError::Toml(err) => Some(err),
+            SignatureError::Json(err) => Some(err),
+            SignatureError::Pattern { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SignatureError {
+    fn from(err: io::Error) -> Self {
+        SignatureError::Io(err)
+    }
+}
+
+/// Error returned when resolving a signature fails
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ResolveError {
+    /// The pattern was not found
+    NotFound,
+    /// The image has no section with this name
+    SectionNotFound(String),
+    /// Memory at this address is not part of the image
+    Unmapped(u64),
+    /// An address computation overflowed
+    Overflow,
+    /// Reading the image failed
+    Io(io::ErrorKind),
+}
+
+impl fmt::Display for ResolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResolveError::NotFound => f.write_str("pattern not found"),
+            ResolveError::SectionNotFound(name) => write!(f, "section {name:?} not found"),
+            ResolveError::Unmapped(address) => write!(f, "address {address:#x} is not mapped"),
+            ResolveError::Overflow => f.write_str("address computation overflowed"),
+            ResolveError::Io(kind) => write!(f, "failed to read image: {kind}"),
+        }
+    }
+}
+
+impl Error for ResolveError {}
+
+/// Post-processing step applied to the address of a match
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Step {
+    /// Add a signed offset to the address
+    Add {
+        /// Offset to add
+        offset: i64,
+    },
+    /// Resolve a 32-bit relative displacement, as in `call rel32` or `mov rax, [rip + disp32]`
+    ///
+    /// The displacement is read `disp_offset` bytes past the address and added to the end of the
+    /// instruction, which is `instr_len` bytes past the address.
+    Rel32 {
+        /// Offset of the displacement from the address
+        disp_offset: usize,
+        /// Length of the instruction
+        instr_len: usize,
+    },
+    /// Read a little-endian pointer at the address, the pointer size is given by the image
+    Deref,
+}
+
+impl Step {
+    /// Apply the step to an address of the image
+    pub fn apply(&self, address: u64, image: &(impl Image + ?Sized)) -> Result<u64, ResolveError> {
+        match *self {
+            Step::Add { offset } => address
+                .checked_add_signed(offset)
+                .ok_or(ResolveError::Overflow),
+            Step::Rel32 {
+                disp_offset,
+                instr_len,
+            } => {
+                let mut disp = [0; 4];
+                image.read(offset(address, disp_offset)?, &mut disp)?;
+
+                offset(address, instr_len)?
+                    .checked_add_signed(i32::from_le_bytes(disp).into())
+                    .ok_or(ResolveError::Overflow)
+            }
+            Step::Deref => {
+                let mut pointer = [0; 8];
+                let size = image.pointer_size().min(pointer.len());
+                image.read(address, &mut pointer[..size])?;
+
+                Ok(u64::from_le_bytes(pointer))
+            }
+        }
+    }
+}
+
+fn offset(address: u64, offset: usize) -> Result<u64, ResolveError> {
+    address
+        .checked_add(offset as u64)
+        .ok_or(ResolveError::Overflow)
+}
+
+/// Something signatures can be resolved against, such as an executable file or process memory
+///
+/// Addresses are virtual addresses for executable files and memory dumps, remote addresses
+/// for processes and offsets for plain byte slices.
+pub trait Image {
+    /// Find the first occurence of the pattern, optionally restricted to a module or section
+    ///
+    /// Returns the address of the match, including the result offset of the pattern.
+    fn find(
+        &self,
+        scanner: &Scanner,
+        module: Option<&str>,
+        section: Option<&str>,
+    ) -> Result<u64, ResolveError>;
+
+    /// Fill `buffer` with the bytes at `address`
+    fn read(&self, address: u64, buffer: &mut [u8]) -> Result<(), ResolveError>;
+
+    /// Get the size of a pointer in bytes
+    fn pointer_size(&self) -> usize;
+}
+
+/// A plain byte slice, addresses are offsets into the slice
+///
+/// The slice has no modules or sections, module names are ignored and scanning a section fails.
+impl Image for [u8] {
+    fn find(
+        &self,
+        scanner: &Scanner,
+        _module: Option<&str>,
+        section: Option<&str>,
+    ) -> Result<u64, ResolveError> {
+        if let Some(section) = section {
+            return Err(ResolveError::SectionNotFound(section.to_string()));
+        }
+
+        let result = scanner.find_in(self).ok_or(ResolveError::NotFound)?;
+        Ok(result.offset() as u64)
+    }
+
+    fn read(&self, address: u64, buffer: &mut [u8]) -> Result<(), ResolveError> {
+        buffer.copy_from_slice(read_file(
+            self,
+            usize::try_from(address).ok(),
+            address,
+            buffer.len(),
+        )?);
+        Ok(())
+    }
+
+    fn pointer_size(&self) -> usize {
+        mem::size_of::<usize>()
+    }
+}
+
+/// A PE image, addresses are virtual addresses based at the preferred image base
+///
+/// The image is a single module, module names are ignored. Without a section,
+/// the executable sections are scanned.
+impl Image for crate::pe::Pe<'_> {
+    fn find(
+        &self,
+        scanner: &Scanner,
+        _module: Option<&str>,
+        section: Option<&str>,
+    ) -> Result<u64, ResolveError> {
+        let result = match section {
+            Some(section) => {
+                self.section(section)
+                    .ok_or_else(|| ResolveError::SectionNotFound(section.to_string()))?;
+                self.find_in_section(scanner, section)
+            }
+            None => self.find_in_executable_sections(scanner),
+        }
+        .ok_or(ResolveError::NotFound)?;
+
+        self.offset_to_va(result.offset())
+            .ok_or(ResolveError::Unmapped(result.offset() as u64))
+    }
+
+    fn read(&self, address: u64, buffer: &mut [u8]) -> Result<(), ResolveError> {
+        let offset = self
+            .va_to_rva(address)
+            .and_then(|rva| self.rva_to_offset(rva));
+
+        buffer.copy_from_slice(read_file(self.data(), offset, address, buffer.len())?);
+        Ok(())
+    }
+
+    fn pointer_size(&self) -> usize {
+        if self.is_64() {
+            8
+        } else {
+            4
+        }
+    }
+}
+
+/// An ELF file, addresses are the virtual addresses of its loaded segments
+///
+/// The file is a single module, module names are ignored. Without a section,
+/// the executable segments are scanned.
+impl Image for crate::elf::Elf<'_> {
+    fn find(
+        &self,
+        scanner: &Scanner,
+        _module: Option<&str>,
+        section: Option<&str>,
+    ) -> Result<u64, ResolveError> {
+        let result = match section {
+            Some(section) => {
+                self.section(section)
+                    .ok_or_else(|| ResolveError::SectionNotFound(section.to_string()))?;
+                self.find_in_section(scanner, section)
+            }
+            None => self.find_in_executable_segments(scanner),
+        }
+        .ok_or(ResolveError::NotFound)?;
+
+        self.offset_to_address(result.offset())
+            .ok_or(ResolveError::Unmapped(result.offset() as u64))
+    }
+
+    fn read(&self, address: u64, buffer: &mut [u8]) -> Result<(), ResolveError> {
+        let offset = self.address_to_offset(address);
+
+        buffer.copy_from_slice(read_file(self.data(), offset, address, buffer.len())?);
+        Ok(())
+    }
+
+    fn pointer_size(&self) -> usize {
+        if self.is_64() {
+            8
+        } else {
+            4
+        }
+    }
+}
+
+/// A Mach-O image, addresses are the virtual addresses of its segments
+///
+/// The image is a single module, module names are ignored. Sections are named
+/// `segment,section` as in `__TEXT,__text`, a name without a segment matches the first section
+/// with that name. Without a section, the executable sections are scanned.
+impl Image for crate::macho::MachO<'_> {
+    fn find(
+        &self,
+        scanner: &Scanner,
+        _module: Option<&str>,
+        section: Option<&str>,
+    ) -> Result<u64, ResolveError> {
+        let result = match section {
+            Some(name) => {
+                let section = match name.split_once(',') {
+                    Some((segment_name, section_name)) => self.section(segment_name, section_name),
+                    None => self
+                        .sections()
+                        .iter()
+                        .find(|section| section.name() == name),
+                }
+                .ok_or_else(|| ResolveError::SectionNotFound(name.to_string()))?;
+
+                self.find_in_section(scanner, section.segment_name(), section.name())
+            }
+            None => self.find_in_executable_sections(scanner),
+        }
+        .ok_or(ResolveError::NotFound)?;
+
+        self.offset_to_vmaddr(result.offset())
+            .ok_or(ResolveError::Unmapped(result.offset() as u64))
+    }
+
+    fn read(&self, address: u64, buffer: &mut [u8]) -> Result<(), ResolveError> {
+        let offset = self.vmaddr_to_offset(address);
+
+        buffer.copy_from_slice(read_file(self.data(), offset, address, buffer.len())?);
+        Ok(())
+    }
+
+    fn pointer_size(&self) -> usize {
+        if self.is_64() {
+            8
+        } else {
+            4
+        }
+    }
+}
+
+/// A memory dump, addresses are the virtual addresses of the dumped process
+///
+/// Dumps carry no module or section information, module names are ignored and
+/// scanning a section fails. Pointers are assumed to be the size of the host pointers.
+impl Image for crate::dump::MemoryDump<'_> {
+    fn find(
+        &self,
+        scanner: &Scanner,
+        _module: Option<&str>,
+        section: Option<&str>,
+    ) -> Result<u64, ResolveError> {
+        if let Some(section) = section {
+            return Err(ResolveError::SectionNotFound(section.to_string()));
+        }
+
+        crate::dump::MemoryDump::find(self, scanner).ok_or(ResolveError::NotFound)
+    }
+
+    fn read(&self, address: u64, buffer: &mut [u8]) -> Result<(), ResolveError> {
+        let data = crate::dump::MemoryDump::read(self, address, buffer.len())
+            .ok_or(ResolveError::Unmapped(address))?;
+
+        buffer.copy_from_slice(data);
+        Ok(())
+    }
+
+    fn pointer_size(&self) -> usize {
+        mem::size_of::<usize>()
+    }
+}
+
+/// A running process, addresses are remote addresses
+///
+/// A module restricts the scan to the regions mapping a file with that name, such as `libc.so.6`,
+/// otherwise every readable region is scanned. Sections are not supported.
+#[cfg(target_os = "linux")]
+impl Image for crate::process::Process {
+    fn find(
+        &self,
+        scanner: &Scanner,
+        module: Option<&str>,
+        section: Option<&str>,
+    ) -> Result<u64, ResolveError> {
+        if let Some(section) = section {
+            return Err(ResolveError::SectionNotFound(section.to_string()));
+        }
+
+        let result = crate::process::Process::find(self, scanner, |region| match module {
+            Some(module) => region
+                .path()
+                .and_then(Path::file_name)
+                .is_some_and(|name| name == module),
+            None => true,
+        })
+        .map_err(|err| ResolveError::Io(err.kind()))?;
+
+        result
+            .map(|address| address as u64)
+            .ok_or(ResolveError::NotFound)
+    }
+
+    fn read(&self, address: u64, buffer: &mut [u8]) -> Result<(), ResolveError> {
+        let remote = usize::try_from(address).map_err(|_| ResolveError::Unmapped(address))?;
+
+        match crate::process::Process::read(self, remote, buffer) {
+            Ok(read) if read == buffer.len() => Ok(()),
+            Ok(_) => Err(ResolveError::Unmapped(address)),
+            Err(err) => Err(ResolveError::Io(err.kind())),
+        }
+    }
+
+    fn pointer_size(&self) -> usize {
+        mem::size_of::<usize>()
+    }
+}
+
+/// Get `len` bytes of a file at `offset`, `address` is the address the offset was translated from
+fn read_file(
+    data: &[u8],
+    offset: Option<usize>,
+    address: u64,
+    len: usize,
+) -> Result<&[u8], ResolveError> {
+    offset
+        .and_then(|offset| data.get(offset..offset.checked_add(len)?))
+        .ok_or(ResolveError::Unmapped(address))
+}
+
+/// A named pattern and the steps that resolve a match to an address
+#[derive(Debug, Clone)]
+pub struct Signature {
+    name: String,
+    scanner: Scanner,
+    module: Option<String>,
+    section: Option<String>,
+    steps: Vec<Step>,
+}
+
+impl Signature {
+    fn from_raw(raw: RawSignature) -> Result<Self, SignatureError> {
+        let mut pattern =
+            Pattern::parse(&raw.pattern).map_err(|error| SignatureError::Pattern {
+                name: raw.name.clone(),
+                error,
+            })?;
+
+        if let Some(result_offset) = raw.result_offset {
+            if result_offset > pattern.unpadded_size {
+                return Err(SignatureError::ResultOffsetOutOfBounds(raw.name));
+            }
+
+            pattern = pattern.with_result_offset(result_offset);
+        }
+
+        Ok(Signature {
+            name: raw.name,
+            scanner: Scanner::from(pattern),
+            module: raw.module,
+            section: raw.section,
+            steps: raw.steps,
+        })
+    }
+
+    /// Get the name of the signature
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Get the pattern of the signature
+    pub fn pattern(&self) -> &Pattern {
+        &self.scanner.0
+    }
+
+    /// Get the scanner for the pattern of the signature
+    pub fn scanner(&self) -> &Scanner {
+        &self.scanner
+    }
+
+    /// Get the module the pattern is scanned in, if restricted to one
+    pub fn module(&self) -> Option<&str> {
+        self.module.as_deref()
+    }
+
+    /// Get the section the pattern is scanned in, if restricted to one
+    pub fn section(&self) -> Option<&str> {
+        self.section.as_deref()
+    }
+
+    /// Get the post-processing steps, in the order they are applied
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Resolve the signature against an image
+    ///
+    /// Finds the pattern and applies the steps of the signature to the address of the match.
+    pub fn resolve(&self, image: &(impl Image + ?Sized)) -> Result<u64, ResolveError> {
+        let address = image.find(&self.scanner, self.module(), self.section())?;
+
+        self.steps
+            .iter()
+            .try_fold(address, |address, step| step.apply(address, image))
+    }
+}
+
+/// Signature file entry, as written in the file
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSignature {
+    name: String,
+    pattern: String,
+    #[serde(default)]
+    module: Option<String>,
+    #[serde(default)]
+    section: Option<String>,
+    #[serde(default)]
+    result_offset: Option<usize>,
+    #[serde(default)]
+    steps: Vec<Step>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawSignatureFile {
+    #[serde(default, rename = "signature", alias = "signatures")]
+    signatures: Vec<RawSignature>,
+}
+
+/// A set of signatures with unique names, loaded from a signature file
+///
+/// # Example
+///
+/// ```no_run
+/// use lightningscanner::pe::Pe;
+/// use lightningscanner::signature::SignatureSet;
+///
+/// let signatures = SignatureSet::load("signatures.toml").unwrap();
+///
+/// let data = std::fs::read("game.exe").unwrap();
+/// let pe = Pe::parse(&data).unwrap();
+///
+/// let resolution = signatures.resolve_all(&pe);
+/// for (name, address) in resolution.addresses() {
+///     println!("{name}: {address:#x}");
+/// }
+/// for (name, error) in resolution.failures() {
+///     eprintln!("{name}: {error}");
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SignatureSet {
+    signatures: Vec<Signature>,
+}
+
+impl SignatureSet {
+    /// Load a signature file, the format is chosen by the `.toml` or `.json` file extension
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SignatureError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("toml") => Self::from_toml_str(&fs::read_to_string(path)?),
+            Some("json") => Self::from_json_str(&fs::read_to_string(path)?),
+            _ => Err(SignatureError::UnknownFormat),
+        }
+    }
+
+    /// Parse a TOML signature file
+    pub fn from_toml_str(toml: &str) -> Result<Self, SignatureError> {
+        let file = toml::from_str(toml).map_err(SignatureError::Toml)?;
+        Self::from_raw(file)
+    }
+
+    /// Parse a JSON signature file
+    pub fn from_json_str(json: &str) -> Result<Self, SignatureError> {
+        let file = serde_json::from_str(json).map_err(SignatureError::Json)?;
+        Self::from_raw(file)
+    }
+
+    fn from_raw(file: RawSignatureFile) -> Result<Self, SignatureError> {
+        let mut signatures = Vec::<Signature>::with_capacity(file.signatures.len());
+
+        for raw in file.signatures {
+            if signatures
+                .iter()
+                .any(|signature| signature.name == raw.name)
+            {
+                return Err(SignatureError::DuplicateName(raw.name));
+            }
+
+            signatures.push(Signature::from_raw(raw)?);
+        }
+
+        Ok(SignatureSet { signatures })
+    }
+
+    /// Get the signatures in the order they are listed in the file
+    pub fn signatures(&self) -> &[Signature] {
+        &self.signatures
+    }
+
+    /// Get the signature with the given name
+    pub fn get(&self, name: &str) -> Option<&Signature> {
+        self.signatures
+            .iter()
+            .find(|signature| signature.name == name)
+    }
+
+    /// Get the number of signatures
+    pub fn len(&self) -> usize {
+        self.signatures.len()
+    }
+
+    /// Check if there are no signatures
+    pub fn is_empty(&self) -> bool {
+        self.signatures.is_empty()
+    }
+
+    /// Resolve every signature against an image
+    ///
+    /// Signatures that fail to resolve don't stop the others from being resolved,
+    /// they are reported by [`Resolution::failures`].
+    pub fn resolve_all(&self, image: &(impl Image + ?Sized)) -> Resolution {
+        let mut resolution = Resolution::default();
+
+        for signature in &self.signatures {
+            match signature.resolve(image) {
+                Ok(address) => {
+                    resolution.addresses.insert(signature.name.clone(), address);
+                }
+                Err(err) => resolution.failures.push((signature.name.clone(), err)),
+            }
+        }
+
+        resolution
+    }
+}
+
+/// Result of resolving a [`SignatureSet`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Resolution {
+    addresses: HashMap<String, u64>,
+    failures: Vec<(String, ResolveError)>,
+}
+
+impl Resolution {
+    /// Get the resolved addresses by signature name
+    pub fn addresses(&self) -> &HashMap<String, u64> {
+        &self.addresses
+    }
+
+    /// Get the resolved address of the named signature
+    pub fn get(&self, name: &str) -> Option<u64> {
+        self.addresses.get(name).copied()
+    }
+
+    /// Get the signatures that failed to resolve and why, in file order
+    pub fn failures(&self) -> &[(String, ResolveError)] {
+        &self.failures
+    }
+
+    /// Check if every signature was resolved
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
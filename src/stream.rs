@@ -0,0 +1,155 @@
+//! Scanning of streams that don't fit in memory
+
+use crate::{ScanMode, Scanner};
+use std::io::{self, Read};
+
+/// Scanner over a [`Read`] source
+///
+/// The stream is read in fixed-size blocks, the last `unpadded_size - 1` bytes of every block
+/// are carried over to the next one, so matches crossing block boundaries are found too.
+/// Yields the absolute stream offset of every match, including the result offset of the pattern.
+///
+/// # Example
+///
+/// ```
+/// use lightningscanner::{Scanner, StreamScanner};
+///
+/// let stream: &[u8] = &[0xe8, 0x10, 0x00, 0xe8, 0x20, 0x00];
+///
+/// let offsets = StreamScanner::new(Scanner::new("e8 ?? 00"), stream)
+///     .with_block_size(4)
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+///
+/// assert_eq!(offsets, [0, 3]);
+/// ```
+pub struct StreamScanner<R> {
+    scanner: Scanner,
+    reader: R,
+    preferred_scan_mode: Option<ScanMode>,
+    block_size: usize,
+    buffer: Vec<u8>,
+    /// Stream offset of the first byte of the buffer
+    buffer_offset: u64,
+    /// Buffer position of the next possible match start
+    position: usize,
+    eof: bool,
+}
+
+impl<R: Read> StreamScanner<R> {
+    const DEFAULT_BLOCK_SIZE: usize = 1024 * 1024;
+
+    /// Create a new [`StreamScanner`] instance reading from `reader`
+    pub fn new(scanner: Scanner, reader: R) -> Self {
+        StreamScanner {
+            scanner,
+            reader,
+            preferred_scan_mode: None,
+            block_size: Self::DEFAULT_BLOCK_SIZE,
+            buffer: Vec::new(),
+            buffer_offset: 0,
+            position: 0,
+            eof: false,
+        }
+    }
+
+    /// Set the number of bytes read from the stream at once
+    ///
+    /// # Panics
+    ///
+    /// Panics if `block_size` is zero.
+    pub fn with_block_size(mut self, block_size: usize) -> Self {
+        assert!(block_size > 0, "block size must not be zero");
+
+        self.block_size = block_size;
+        self
+    }
+
+    /// Set the preferred scan mode (Avx2, Sse42, Scalar)
+    ///
+    /// If the preferred mode is not available, will choose the fastest out of the availble ones.
+    pub fn with_scan_mode(mut self, preferred_scan_mode: ScanMode) -> Self {
+        self.preferred_scan_mode = Some(preferred_scan_mode);
+        self
+    }
+
+    /// Get the scanner used for every block
+    pub fn scanner(&self) -> &Scanner {
+        &self.scanner
+    }
+
+    /// Unwrap the underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// Drop the bytes no match can start in anymore and append the next block of the stream
+    fn read_block(&mut self) -> io::Result<()> {
+        let carry_over = self.scanner.0.unpadded_size.saturating_sub(1);
+        let consumed = self
+            .buffer
+            .len()
+            .saturating_sub(carry_over)
+            .max(self.position)
+            .min(self.buffer.len());
+
+        self.buffer.drain(..consumed);
+        self.buffer_offset += consumed as u64;
+        self.position = self.position.saturating_sub(consumed);
+
+        let filled = self.buffer.len();
+        self.buffer.resize(filled + self.block_size, 0);
+
+        let read = loop {
+            match self.reader.read(&mut self.buffer[filled..]) {
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                result => break result,
+            }
+        };
+
+        let read = match read {
+            Ok(read) => read,
+            Err(err) => {
+                self.buffer.truncate(filled);
+                return Err(err);
+            }
+        };
+
+        self.buffer.truncate(filled + read);
+        self.eof = read == 0;
+
+        Ok(())
+    }
+}
+
+impl<R: Read> Iterator for StreamScanner<R> {
+    type Item = io::Result<u64>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if self.position <= self.buffer.len() {
+                let result = self
+                    .scanner
+                    .find_in_with_mode(self.preferred_scan_mode, &self.buffer[self.position..]);
+
+                if let Some(result) = result {
+                    let start = self.position + result.start();
+
+                    // resume right after the start of the match, so overlapping matches are reported too
+                    self.position = start + 1;
+                    return Some(Ok(
+                        self.buffer_offset + (start + self.scanner.0.result_offset) as u64
+                    ));
+                }
+            }
+
+            if self.eof {
+                return None;
+            }
+
+            if let Err(err) = self.read_block() {
+                return Some(Err(err));
+            }
+        }
+    }
+}
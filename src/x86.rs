@@ -0,0 +1,366 @@
+//! Minimal x86-64 instruction decoding for relative operands and instruction lengths
+
+use std::ops::Range;
+
+/// Location of a relative displacement inside of an instruction
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) struct RelativeOperand {
+    /// Offset of the displacement from the start of the instruction
+    pub disp_offset: usize,
+    /// Size of the displacement in bytes, either 1 or 4
+    pub disp_size: usize,
+    /// Length of the whole instruction
+    pub instr_len: usize,
+}
+
+impl RelativeOperand {
+    const fn rel8(disp_offset: usize) -> Self {
+        RelativeOperand {
+            disp_offset,
+            disp_size: 1,
+            instr_len: disp_offset + 1,
+        }
+    }
+
+    const fn rel32(disp_offset: usize) -> Self {
+        RelativeOperand {
+            disp_offset,
+            disp_size: 4,
+            instr_len: disp_offset + 4,
+        }
+    }
+
+    /// Read the sign-extended displacement, `byte` is only called for bytes of the instruction
+    pub fn read_disp(&self, byte: impl Fn(usize) -> Option<u8>) -> Option<i64> {
+        if self.disp_size == 1 {
+            return byte(self.disp_offset).map(|disp| disp as i8 as i64);
+        }
+
+        let mut disp = [0; 4];
+        for (i, disp_byte) in disp.iter_mut().enumerate() {
+            *disp_byte = byte(self.disp_offset + i)?;
+        }
+
+        Some(i32::from_le_bytes(disp) as i64)
+    }
+}
+
+/// Decode a `call rel32` instruction
+pub(crate) fn decode_call(byte: impl Fn(usize) -> Option<u8>) -> Option<RelativeOperand> {
+    match byte(0)? {
+        0xe8 => Some(RelativeOperand::rel32(1)),
+        _ => None,
+    }
+}
+
+/// Decode a `jmp` or `jcc` instruction with a rel8 or rel32 operand
+pub(crate) fn decode_jmp(byte: impl Fn(usize) -> Option<u8>) -> Option<RelativeOperand> {
+    match byte(0)? {
+        0xe9 => Some(RelativeOperand::rel32(1)),
+        0xeb | 0x70..=0x7f => Some(RelativeOperand::rel8(1)),
+        0x0f => match byte(1)? {
+            0x80..=0x8f => Some(RelativeOperand::rel32(2)),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// One-byte opcodes with a ModR/M operand and no immediate
+const MODRM_OPCODES: [u8; 27] = [
+    0x01, 0x03, 0x09, 0x0b, 0x11, 0x13, 0x19, 0x1b, 0x21, 0x23, 0x29, 0x2b, 0x31, 0x33, 0x39, 0x3b,
+    0x63, 0x84, 0x85, 0x86, 0x87, 0x88, 0x89, 0x8a, 0x8b, 0x8d, 0xff,
+];
+
+/// Decode an instruction with a RIP-relative memory operand and no immediate,
+/// such as `mov`, `lea`, `cmp` or `call/jmp qword ptr [rip + disp32]`
+pub(crate) fn decode_rip_relative(byte: impl Fn(usize) -> Option<u8>) -> Option<RelativeOperand> {
+    let mut offset = 0;
+
+    // operand size override and REX prefixes
+    if byte(offset)? == 0x66 {
+        offset += 1;
+    }
+    if let 0x40..=0x4f = byte(offset)? {
+        offset += 1;
+    }
+
+    match byte(offset)? {
+        opcode if MODRM_OPCODES.contains(&opcode) => offset += 1,
+        // movzx, movsx
+        0x0f => match byte(offset + 1)? {
+            0xb6 | 0xb7 | 0xbe | 0xbf => offset += 2,
+            _ => return None,
+        },
+        _ => return None,
+    }
+
+    // mod = 00, r/m = 101 selects [rip + disp32]
+    if byte(offset)? & 0xc7 != 0x05 {
+        return None;
+    }
+
+    Some(RelativeOperand::rel32(offset + 1))
+}
+
+/// Length of a decoded instruction and the location of its address-dependent operand
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Instruction {
+    /// Length of the whole instruction
+    pub len: usize,
+    /// Bytes that change when code or data moves, such as branch displacements,
+    /// RIP-relative and absolute displacements or 64-bit immediate addresses
+    pub volatile: Option<Range<usize>>,
+}
+
+/// Size of the immediate operand of an opcode
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Immediate {
+    None,
+    Byte,
+    Word,
+    /// 16 bits with an operand size override and without REX.W, 32 bits otherwise
+    Dword,
+    /// 64 bits with REX.W, same as [`Immediate::Dword`] otherwise
+    Qword,
+    /// `enter imm16, imm8`
+    Enter,
+    /// 8-bit branch displacement
+    Rel8,
+    /// 32-bit branch displacement
+    Rel32,
+    /// 64-bit absolute address, 32 bits with an address size override
+    Offset,
+}
+
+/// Operands of an opcode
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Operands {
+    modrm: bool,
+    immediate: Immediate,
+}
+
+impl Operands {
+    const fn new(modrm: bool, immediate: Immediate) -> Option<Self> {
+        Some(Operands { modrm, immediate })
+    }
+}
+
+/// Decode the length of an x86-64 instruction
+///
+/// Covers the general purpose, x87, SSE, VEX and EVEX encoded instructions,
+/// returns `None` for invalid or unsupported encodings.
+pub(crate) fn decode_length(byte: impl Fn(usize) -> Option<u8>) -> Option<Instruction> {
+    let mut offset = 0;
+    let mut operand_size_override = false;
+    let mut address_size_override = false;
+    let mut rex_w = false;
+
+    loop {
+        match byte(offset)? {
+            0x66 => operand_size_override = true,
+            0x67 => address_size_override = true,
+            0xf0 | 0xf2 | 0xf3 | 0x26 | 0x2e | 0x36 | 0x3e | 0x64 | 0x65 => {}
+            _ => break,
+        }
+
+        offset += 1;
+        if offset > 14 {
+            return None;
+        }
+    }
+
+    if let rex @ 0x40..=0x4f = byte(offset)? {
+        rex_w = rex & 0x08 != 0;
+        offset += 1;
+    }
+
+    let opcode = byte(offset)?;
+    offset += 1;
+
+    let operands = match opcode {
+        // VEX and EVEX prefixes, followed by the opcode of the selected map
+        0xc4 | 0xc5 | 0x62 => {
+            let (map, prefix_len) = match opcode {
+                0xc5 => (1, 1),
+                0xc4 => (byte(offset)? & 0x1f, 2),
+                _ => (byte(offset)? & 0x07, 3),
+            };
+            offset += prefix_len;
+
+            let opcode = byte(offset)?;
+            offset += 1;
+
+            vex_operands(map, opcode)?
+        }
+        0x0f => {
+            let opcode = byte(offset)?;
+            offset += 1;
+
+            match opcode {
+                0x38 => {
+                    offset += 1;
+                    Operands::new(true, Immediate::None)?
+                }
+                0x3a => {
+                    offset += 1;
+                    Operands::new(true, Immediate::Byte)?
+                }
+                _ => two_byte_operands(opcode)?,
+            }
+        }
+        _ => one_byte_operands(opcode)?,
+    };
+
+    let mut immediate = operands.immediate;
+    let mut volatile = None;
+
+    if operands.modrm {
+        let modrm = byte(offset)?;
+        let (mode, rm) = (modrm >> 6, modrm & 0x07);
+        offset += 1;
+
+        let disp_size = match (mode, rm) {
+            (0b11, _) => 0,
+            (0b00, 0b101) => {
+                // [rip + disp32]
+                volatile = Some(offset..offset + 4);
+                4
+            }
+            (mode, 0b100) => {
+                let sib = byte(offset)?;
+                offset += 1;
+
+                match mode {
+                    0b00 if sib & 0x07 == 0b101 => {
+                        // [index * scale + disp32] without a base register
+                        volatile = Some(offset..offset + 4);
+                        4
+                    }
+                    0b00 => 0,
+                    0b01 => 1,
+                    _ => 4,
+                }
+            }
+            (0b01, _) => 1,
+            (0b10, _) => 4,
+            _ => 0,
+        };
+        offset += disp_size;
+
+        // test r/m, imm is the only member of group 3 with an immediate
+        if matches!(opcode, 0xf6 | 0xf7) && (modrm >> 3) & 0x07 >= 2 {
+            immediate = Immediate::None;
+        }
+    }
+
+    // REX.W takes precedence over the operand size override
+    let dword_size = if operand_size_override && !rex_w {
+        2
+    } else {
+        4
+    };
+    let (immediate_size, is_volatile) = match immediate {
+        Immediate::None => (0, false),
+        Immediate::Byte => (1, false),
+        Immediate::Word => (2, false),
+        Immediate::Dword => (dword_size, false),
+        Immediate::Qword if rex_w => (8, true),
+        Immediate::Qword => (dword_size, false),
+        Immediate::Enter => (3, false),
+        Immediate::Rel8 => (1, true),
+        Immediate::Rel32 => (4, true),
+        Immediate::Offset if address_size_override => (4, true),
+        Immediate::Offset => (8, true),
+    };
+
+    if is_volatile {
+        volatile = Some(offset..offset + immediate_size);
+    }
+
+    Some(Instruction {
+        len: offset + immediate_size,
+        volatile,
+    })
+}
+
+fn one_byte_operands(opcode: u8) -> Option<Operands> {
+    use Immediate::{Byte, Dword, Enter, Offset, Qword, Rel32, Rel8, Word};
+
+    match opcode {
+        // invalid in 64-bit mode
+        0x06 | 0x07 | 0x0e | 0x16 | 0x17 | 0x1e | 0x1f | 0x27 | 0x2f | 0x37 | 0x3f | 0x60
+        | 0x61 | 0x82 | 0x9a | 0xce | 0xd4 | 0xd5 | 0xd6 | 0xea => None,
+        // add, or, adc, sbb, and, sub, xor, cmp
+        0x00..=0x3f => match opcode & 0x07 {
+            0..=3 => Operands::new(true, Immediate::None),
+            4 => Operands::new(false, Byte),
+            _ => Operands::new(false, Dword),
+        },
+        0x50..=0x5f | 0x6c..=0x6f | 0x90..=0x9f | 0xa4..=0xa7 | 0xaa..=0xaf => {
+            Operands::new(false, Immediate::None)
+        }
+        0x63 | 0x84..=0x8f | 0xd0..=0xd3 | 0xd8..=0xdf | 0xfe | 0xff => {
+            Operands::new(true, Immediate::None)
+        }
+        0x68 => Operands::new(false, Dword),
+        0x69 | 0x81 | 0xc7 => Operands::new(true, Dword),
+        0x6a | 0xa8 | 0xb0..=0xb7 | 0xcd | 0xe4..=0xe7 => Operands::new(false, Byte),
+        0x6b | 0x80 | 0x83 | 0xc0 | 0xc1 | 0xc6 => Operands::new(true, Byte),
+        0x70..=0x7f | 0xe0..=0xe3 | 0xeb => Operands::new(false, Rel8),
+        0xa0..=0xa3 => Operands::new(false, Offset),
+        0xa9 => Operands::new(false, Dword),
+        0xb8..=0xbf => Operands::new(false, Qword),
+        0xc2 | 0xca => Operands::new(false, Word),
+        0xc3
+        | 0xc9
+        | 0xcb
+        | 0xcc
+        | 0xcf
+        | 0xd7
+        | 0xec..=0xef
+        | 0xf1
+        | 0xf4
+        | 0xf5
+        | 0xf8..=0xfd => Operands::new(false, Immediate::None),
+        0xc8 => Operands::new(false, Enter),
+        0xe8 | 0xe9 => Operands::new(false, Rel32),
+        0xf6 => Operands::new(true, Byte),
+        0xf7 => Operands::new(true, Dword),
+        _ => None,
+    }
+}
+
+fn two_byte_operands(opcode: u8) -> Option<Operands> {
+    use Immediate::{Byte, Rel32};
+
+    match opcode {
+        0x04
+        | 0x0a
+        | 0x0c
+        | 0x0e
+        | 0x0f
+        | 0x24..=0x27
+        | 0x36
+        | 0x39
+        | 0x3b..=0x3f
+        | 0xa6
+        | 0xa7 => None,
+        0x05..=0x09 | 0x0b | 0x30..=0x37 | 0x77 | 0xa0..=0xa2 | 0xa8..=0xaa | 0xc8..=0xcf => {
+            Operands::new(false, Immediate::None)
+        }
+        0x70..=0x73 | 0xa4 | 0xac | 0xba | 0xc2 | 0xc4..=0xc6 => Operands::new(true, Byte),
+        0x80..=0x8f => Operands::new(false, Rel32),
+        _ => Operands::new(true, Immediate::None),
+    }
+}
+
+fn vex_operands(map: u8, opcode: u8) -> Option<Operands> {
+    match (map, opcode) {
+        // vzeroupper, vzeroall
+        (1, 0x77) => Operands::new(false, Immediate::None),
+        (1, 0x70..=0x73 | 0xc2 | 0xc4..=0xc6) | (3, _) => Operands::new(true, Immediate::Byte),
+        (1 | 2 | 5 | 6, _) => Operands::new(true, Immediate::None),
+        _ => None,
+    }
+}
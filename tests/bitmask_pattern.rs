@@ -0,0 +1,57 @@
+use lightningscanner::pattern::Pattern;
+use lightningscanner::{ScanMode, Scanner};
+
+// REX.W prefix with any of the R/X/B bits, MOV r64, r/m64 with a register-direct ModRM
+const BYTES: [u8; 3] = [0x48, 0x8b, 0xc0];
+const MASK: [u8; 3] = [0xf8, 0xff, 0xc0];
+
+const DATA_SET: [u8; 48] = [
+    0x48, 0x8b, 0x05, 0x10, 0x20, 0x30, 0x40, 0x40, 0x8b, 0xc8, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+    0x58, 0x8b, 0xc8, 0x10, 0x20, 0x30, 0x40, 0x48, 0x89, 0xc8, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+    0x48, 0x8b, 0x4c, 0x24, 0x20, 0x30, 0x40, 0x4d, 0x8b, 0xf9, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+];
+
+const EXPECTED_FIND: usize = 0x27;
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn avx2() {
+    let scanner = Scanner::from(Pattern::from_bytes_and_mask(&BYTES, &MASK));
+    let result = scanner.find_in_with_mode(Some(ScanMode::Avx2), &DATA_SET);
+
+    assert_eq!(result.unwrap().offset(), EXPECTED_FIND);
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn sse42() {
+    let scanner = Scanner::from(Pattern::from_bytes_and_mask(&BYTES, &MASK));
+    let result = scanner.find_in_with_mode(Some(ScanMode::Sse42), &DATA_SET);
+
+    assert_eq!(result.unwrap().offset(), EXPECTED_FIND);
+}
+
+#[test]
+fn scalar() {
+    let scanner = Scanner::from(Pattern::from_bytes_and_mask(&BYTES, &MASK));
+    let result = scanner.find_in_with_mode(Some(ScanMode::Scalar), &DATA_SET);
+
+    assert_eq!(result.unwrap().offset(), EXPECTED_FIND);
+}
+
+#[test]
+fn exact_bytes() {
+    let scanner = Scanner::from(Pattern::from_bytes(&[0x40, 0x48, 0x89]));
+    let offsets = scanner
+        .find_all_in(&DATA_SET)
+        .map(|result| result.offset())
+        .collect::<Vec<_>>();
+
+    assert_eq!(offsets, [0x16]);
+}
+
+#[test]
+#[should_panic]
+fn mismatched_mask() {
+    Pattern::from_bytes_and_mask(&BYTES, &MASK[..2]);
+}
@@ -0,0 +1,83 @@
+use lightningscanner::pattern::{Pattern, PatternErrorKind};
+use lightningscanner::{ScanMode, Scanner};
+
+const BYTES: &str =
+    r"\x40\x57\x48\x83\xEC\x00\x48\xC7\x44\x24\x00\x00\x00\x00\x00\x48\x89\x5C\x24\x00";
+const MASK: &str = "xxxxx?xxxx?????xxxx?";
+
+const DATA_SET: [u8; 48] = [
+    0x40, 0x57, 0x48, 0x83, 0xEC, 0x30, 0x48, 0xC7, 0x44, 0x24, 0x28, 0xFE, 0xFF, 0xFF, 0xFF, 0x48,
+    0x89, 0x6C, 0x24, 0x40, 0x40, 0x57, 0x48, 0x83, 0xEC, 0x30, 0x48, 0xC7, 0x44, 0x24, 0x28, 0xFE,
+    0xFF, 0xFF, 0xFF, 0x48, 0x89, 0x5C, 0x24, 0x40, 0x49, 0x8B, 0xE9, 0x48, 0x8B, 0xF2, 0xcc, 0xcc,
+];
+
+const EXPECTED_FIND: usize = 0x14;
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn avx2() {
+    let scanner = Scanner::from(Pattern::from_code_style(BYTES, MASK).unwrap());
+    let result = scanner.find_in_with_mode(Some(ScanMode::Avx2), &DATA_SET);
+
+    assert_eq!(result.unwrap().offset(), EXPECTED_FIND);
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn sse42() {
+    let scanner = Scanner::from(Pattern::from_code_style(BYTES, MASK).unwrap());
+    let result = scanner.find_in_with_mode(Some(ScanMode::Sse42), &DATA_SET);
+
+    assert_eq!(result.unwrap().offset(), EXPECTED_FIND);
+}
+
+#[test]
+fn scalar() {
+    let scanner = Scanner::from(Pattern::from_code_style(BYTES, MASK).unwrap());
+    let result = scanner.find_in_with_mode(Some(ScanMode::Scalar), &DATA_SET);
+
+    assert_eq!(result.unwrap().offset(), EXPECTED_FIND);
+}
+
+#[test]
+fn round_trip() {
+    let pattern = Pattern::from_code_style(BYTES, MASK).unwrap();
+    let (bytes, mask) = pattern.to_code_style();
+
+    assert_eq!(bytes, BYTES);
+    assert_eq!(mask, MASK);
+}
+
+#[test]
+fn partial_mask_becomes_wildcard() {
+    let (bytes, mask) = Pattern::new("48 8b ?5").to_code_style();
+
+    assert_eq!(bytes, r"\x48\x8B\x00");
+    assert_eq!(mask, "xx?");
+}
+
+#[test]
+fn errors() {
+    let err = Pattern::from_code_style(r"\x48\x8G", "xx").err().unwrap();
+    assert_eq!(err.kind(), PatternErrorKind::InvalidHexDigit);
+    assert_eq!(err.column(), 5);
+    assert_eq!(err.token(), r"\x8G");
+
+    let err = Pattern::from_code_style(r"\x48\x8", "xx").err().unwrap();
+    assert_eq!(err.kind(), PatternErrorKind::DanglingNibble);
+    assert_eq!(err.token(), r"\x8");
+
+    let err = Pattern::from_code_style(r"\x48\n", "xx").err().unwrap();
+    assert_eq!(err.kind(), PatternErrorKind::UnsupportedToken);
+    assert_eq!(err.token(), r"\n");
+
+    let err = Pattern::from_code_style(r"\x48\x89", "x.").err().unwrap();
+    assert_eq!(err.kind(), PatternErrorKind::UnsupportedToken);
+    assert_eq!(err.column(), 2);
+
+    let err = Pattern::from_code_style(r"\x48\x89", "xxx").err().unwrap();
+    assert_eq!(err.kind(), PatternErrorKind::MaskLengthMismatch);
+
+    let err = Pattern::from_code_style("", "").err().unwrap();
+    assert_eq!(err.kind(), PatternErrorKind::EmptyPattern);
+}
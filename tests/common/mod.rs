@@ -0,0 +1,38 @@
+//! Helpers shared by the integration tests
+
+// every test crate compiles this module, but not every one uses all of it
+#![allow(dead_code)]
+
+/// Writer of integers and byte strings into a file built by a test
+pub struct Writer {
+    pub data: Vec<u8>,
+    pub big_endian: bool,
+}
+
+impl Writer {
+    /// Create a writer of `size` zero bytes, writes past the end grow the data
+    pub fn new(size: usize, big_endian: bool) -> Self {
+        Writer {
+            data: vec![0; size],
+            big_endian,
+        }
+    }
+
+    /// Write the low `size` bytes of `value` at `offset`, with the endianness of the writer
+    pub fn put(&mut self, offset: usize, value: u64, size: usize) {
+        if self.big_endian {
+            self.bytes(offset, &value.to_be_bytes()[8 - size..]);
+        } else {
+            self.bytes(offset, &value.to_le_bytes()[..size]);
+        }
+    }
+
+    /// Write `bytes` at `offset`
+    pub fn bytes(&mut self, offset: usize, bytes: &[u8]) {
+        if self.data.len() < offset + bytes.len() {
+            self.data.resize(offset + bytes.len(), 0);
+        }
+
+        self.data[offset..offset + bytes.len()].copy_from_slice(bytes);
+    }
+}
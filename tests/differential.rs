@@ -0,0 +1,176 @@
+use lightningscanner::pattern::Pattern;
+use lightningscanner::{ScanMode, Scanner};
+use tinyrand::{Rand, Wyrand};
+
+const ROUNDS: usize = 500;
+
+/// Generate a pattern of `len` bytes over a small alphabet, with full, nibble and wildcard masks
+fn random_pattern(rand: &mut Wyrand, len: usize) -> Pattern {
+    let bytes = (0..len)
+        .map(|_| (rand.next_u16() % 4) as u8)
+        .collect::<Vec<_>>();
+    let mask = (0..len)
+        .map(|_| [0x00, 0x0f, 0xf0, 0xff, 0xff, 0xff][rand.next_usize() % 6])
+        .collect::<Vec<_>>();
+
+    Pattern::from_bytes_and_mask(&bytes, &mask)
+}
+
+/// Generate a haystack over the same small alphabet, so partial matches are common
+fn random_haystack(rand: &mut Wyrand, len: usize) -> Vec<u8> {
+    (0..len).map(|_| (rand.next_u16() % 4) as u8).collect()
+}
+
+fn first_offsets(scanner: &Scanner, haystack: &[u8], scan_mode: Option<ScanMode>) -> Option<usize> {
+    scanner
+        .find_in_with_mode(scan_mode, haystack)
+        .map(|result| result.offset())
+}
+
+fn all_offsets(scanner: &Scanner, haystack: &[u8], scan_mode: Option<ScanMode>) -> Vec<usize> {
+    scanner
+        .find_all_in_with_mode(scan_mode, haystack)
+        .map(|result| result.offset())
+        .collect()
+}
+
+/// Compare a backend against the scalar backend on random patterns and haystacks
+fn same_as_scalar(scan_mode: Option<ScanMode>) {
+    let mut rand = Wyrand::default();
+
+    for _ in 0..ROUNDS {
+        // patterns span up to three SIMD units, haystacks are often shorter than the pattern
+        let pattern_len = 1 + rand.next_usize() % 96;
+        let haystack_len = rand.next_usize() % 256;
+
+        let pattern = random_pattern(&mut rand, pattern_len);
+        let mut haystack = random_haystack(&mut rand, haystack_len);
+
+        // plant a copy of a long repetitive prefix, so a match overlaps a partial one
+        if haystack.len() > 8 && rand.next_bool(tinyrand::Probability::new(0.5)) {
+            let at = rand.next_usize() % haystack.len();
+            let fill = haystack[at];
+            let end = haystack.len().min(at + 64);
+            haystack[at..end].fill(fill);
+        }
+
+        let scanner = Scanner::from(pattern);
+
+        assert_eq!(
+            first_offsets(&scanner, &haystack, scan_mode),
+            first_offsets(&scanner, &haystack, Some(ScanMode::Scalar)),
+            "{scanner:?} in {haystack:02x?}"
+        );
+        assert_eq!(
+            all_offsets(&scanner, &haystack, scan_mode),
+            all_offsets(&scanner, &haystack, Some(ScanMode::Scalar)),
+            "{scanner:?} in {haystack:02x?}"
+        );
+    }
+}
+
+/// Compare a backend against the scalar backend on random data with planted pattern copies
+///
+/// Bytes are drawn from the whole byte range, so the rare byte prefilter rarely finds candidates.
+fn planted_same_as_scalar(scan_mode: Option<ScanMode>) {
+    let mut rand = Wyrand::default();
+
+    for _ in 0..ROUNDS / 10 {
+        let pattern_len = 1 + rand.next_usize() % 96;
+        let bytes = (0..pattern_len)
+            .map(|_| rand.next_u16() as u8)
+            .collect::<Vec<_>>();
+        let mask = (0..pattern_len)
+            .map(|_| [0x00, 0x0f, 0xff, 0xff][rand.next_usize() % 4])
+            .collect::<Vec<_>>();
+
+        let mut haystack = (0..4096).map(|_| rand.next_u16() as u8).collect::<Vec<_>>();
+        for _ in 0..rand.next_usize() % 4 {
+            let at = rand.next_usize() % (haystack.len() - pattern_len);
+            haystack[at..at + pattern_len].copy_from_slice(&bytes);
+        }
+
+        let scanner = Scanner::from(Pattern::from_bytes_and_mask(&bytes, &mask));
+
+        assert_eq!(
+            all_offsets(&scanner, &haystack, scan_mode),
+            all_offsets(&scanner, &haystack, Some(ScanMode::Scalar)),
+            "{scanner:?}"
+        );
+    }
+}
+
+/// Compare a backend against the scalar backend on data made of two byte values
+///
+/// Every byte of the pattern is common in the haystack, so the prefilter is given up
+/// and the remaining windows are compared directly.
+fn common_bytes_same_as_scalar(scan_mode: Option<ScanMode>) {
+    let mut rand = Wyrand::default();
+
+    for _ in 0..ROUNDS / 10 {
+        let pattern_len = 1 + rand.next_usize() % 48;
+        let bytes = (0..pattern_len)
+            .map(|_| (rand.next_u16() % 2) as u8)
+            .collect::<Vec<_>>();
+        let mask = (0..pattern_len)
+            .map(|_| [0x00, 0x0f, 0xff, 0xff][rand.next_usize() % 4])
+            .collect::<Vec<_>>();
+
+        let haystack_len = 8192 + rand.next_usize() % 64;
+        let haystack = (0..haystack_len)
+            .map(|_| (rand.next_u16() % 2) as u8)
+            .collect::<Vec<_>>();
+
+        let scanner = Scanner::from(Pattern::from_bytes_and_mask(&bytes, &mask));
+
+        assert_eq!(
+            all_offsets(&scanner, &haystack, scan_mode),
+            all_offsets(&scanner, &haystack, Some(ScanMode::Scalar)),
+            "{scanner:?}"
+        );
+    }
+}
+
+/// A match starting inside a longer partial match must not be skipped
+fn match_inside_partial_match(scan_mode: ScanMode) {
+    let mut pattern = vec![0xaa; 40];
+    pattern.push(0xbb);
+
+    let mut haystack = vec![0xaa; 50];
+    haystack.push(0xbb);
+
+    let scanner = Scanner::from(Pattern::from_bytes(&pattern));
+    let result = scanner.find_in_with_mode(Some(scan_mode), &haystack);
+
+    assert_eq!(result.map(|result| result.offset()), Some(10));
+}
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn avx2() {
+    same_as_scalar(Some(ScanMode::Avx2));
+    planted_same_as_scalar(Some(ScanMode::Avx2));
+    common_bytes_same_as_scalar(Some(ScanMode::Avx2));
+    match_inside_partial_match(ScanMode::Avx2);
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn sse42() {
+    same_as_scalar(Some(ScanMode::Sse42));
+    planted_same_as_scalar(Some(ScanMode::Sse42));
+    common_bytes_same_as_scalar(Some(ScanMode::Sse42));
+    match_inside_partial_match(ScanMode::Sse42);
+}
+
+#[test]
+fn scalar() {
+    match_inside_partial_match(ScanMode::Scalar);
+}
+
+#[test]
+fn runtime_detected() {
+    same_as_scalar(None);
+    planted_same_as_scalar(None);
+    common_bytes_same_as_scalar(None);
+}
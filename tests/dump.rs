@@ -0,0 +1,197 @@
+mod common;
+
+use common::Writer;
+use lightningscanner::dump::{DumpError, MemoryDump};
+use lightningscanner::elf::ElfError;
+use lightningscanner::Scanner;
+
+/// A structure the dumped process kept in memory, found by its magic and version
+const STRUCTURE: [u8; 12] = [
+    0xde, 0xc0, 0xad, 0xde, 0x10, 0x20, 0x30, 0x40, 0x01, 0x00, 0x00, 0x00,
+];
+const PATTERN: &str = "de c0 ad de & ?? ?? ?? ?? 01 00 00 00";
+
+/// Memory regions of the dumped process, as address and contents
+fn memory() -> Vec<(u64, Vec<u8>)> {
+    let mut heap = vec![0u8; 0x300];
+    heap[0x40..0x4c].copy_from_slice(&STRUCTURE);
+    heap[0x2f4..].copy_from_slice(&STRUCTURE);
+
+    let mut stack = vec![0xccu8; 0x100];
+    stack[0x80..0x8c].copy_from_slice(&STRUCTURE);
+
+    vec![(0x7ffd_0000_0000, stack), (0x5555_0000_0000, heap)]
+}
+
+/// Build an ELF64 core file with a `PT_NOTE` segment and a `PT_LOAD` segment per region,
+/// plus a `PT_LOAD` segment without file data
+fn build_core() -> Vec<u8> {
+    let memory = memory();
+    let mut w = Writer::new(0, false);
+
+    w.bytes(0, b"\x7fELF\x02\x01\x01");
+    w.put(0x10, 4, 2);
+    w.put(0x12, 0x3e, 2);
+    w.put(0x20, 0x40, 8);
+    w.put(0x36, 0x38, 2);
+    w.put(0x38, memory.len() as u64 + 2, 2);
+    w.put(0x3a, 0x40, 2);
+
+    let mut offset = 0x1000;
+    let mut headers = vec![(4, 0, 0, 0)];
+    for (address, contents) in &memory {
+        headers.push((1, offset as u64, *address, contents.len() as u64));
+        w.bytes(offset, contents);
+        offset += contents.len().next_multiple_of(0x1000);
+    }
+    headers.push((1, w.data.len() as u64, 0x7fff_f000_0000, 0));
+
+    for (index, (kind, offset, address, size)) in headers.into_iter().enumerate() {
+        let header = 0x40 + index * 0x38;
+        w.put(header, kind, 4);
+        w.put(header + 0x4, 4, 4);
+        w.put(header + 0x8, offset, 8);
+        w.put(header + 0x10, address, 8);
+        w.put(header + 0x20, size, 8);
+        w.put(header + 0x28, size.max(0x1000), 8);
+    }
+
+    w.data
+}
+
+/// Build a minidump with the first region in a `MemoryListStream`
+/// and the remaining ones in a `Memory64ListStream`
+fn build_minidump(memory: &[(u64, Vec<u8>)]) -> Vec<u8> {
+    let mut w = Writer::new(0, false);
+
+    w.bytes(0, b"MDMP");
+    w.put(0x4, 0xa793, 4);
+    w.put(0x8, 3, 4);
+    w.put(0xc, 0x20, 4);
+
+    // stream directory: thread list, memory list, memory64 list
+    let streams = [(3, 0x100), (5, 0x200), (9, 0x300)];
+    for (index, (kind, rva)) in streams.into_iter().enumerate() {
+        let entry = 0x20 + index * 0xc;
+        w.put(entry, kind, 4);
+        w.put(entry + 0x8, rva, 4);
+    }
+    w.put(0x100, 0, 4);
+
+    let (address, contents) = &memory[0];
+    w.put(0x200, 1, 4);
+    w.put(0x204, *address, 8);
+    w.put(0x20c, contents.len() as u64, 4);
+    w.put(0x210, 0x800, 4);
+    w.bytes(0x800, contents);
+
+    let base = 0x1000;
+    w.put(0x300, memory.len() as u64 - 1, 8);
+    w.put(0x308, base, 8);
+    let mut offset = base as usize;
+    for (index, (address, contents)) in memory.iter().skip(1).enumerate() {
+        let descriptor = 0x310 + index * 0x10;
+        w.put(descriptor, *address, 8);
+        w.put(descriptor + 0x8, contents.len() as u64, 8);
+        w.bytes(offset, contents);
+        offset += contents.len();
+    }
+
+    w.data
+}
+
+fn scan(dump: &MemoryDump) {
+    let regions = dump
+        .regions()
+        .iter()
+        .map(|region| (region.address(), region.len()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        regions,
+        [(0x5555_0000_0000, 0x300), (0x7ffd_0000_0000, 0x100)]
+    );
+
+    let scanner = Scanner::new(PATTERN);
+    assert_eq!(dump.find(&scanner), Some(0x5555_0000_0044));
+    assert_eq!(
+        dump.find_all(&scanner),
+        [0x5555_0000_0044, 0x5555_0000_02f8, 0x7ffd_0000_0084]
+    );
+
+    assert_eq!(dump.read(0x5555_0000_0044, 4), Some(&STRUCTURE[4..8]));
+    assert_eq!(dump.read(0x5555_0000_02fc, 4), Some(&STRUCTURE[8..]));
+    assert_eq!(dump.read(0x5555_0000_02fc, 5), None);
+    assert_eq!(dump.read(0x1000, 1), None);
+
+    let offset = dump.address_to_offset(0x7ffd_0000_0080).unwrap();
+    assert_eq!(&dump.data()[offset..offset + 4], &STRUCTURE[..4]);
+    assert_eq!(dump.offset_to_address(offset), Some(0x7ffd_0000_0080));
+    assert_eq!(dump.offset_to_address(0), None);
+
+    assert_eq!(dump.find(&Scanner::new("de c0 ad de ff")), None);
+}
+
+#[test]
+fn core_file() {
+    let data = build_core();
+
+    scan(&MemoryDump::parse_core(&data).unwrap());
+    scan(&MemoryDump::parse(&data).unwrap());
+}
+
+#[test]
+fn minidump() {
+    let data = build_minidump(&memory());
+
+    scan(&MemoryDump::parse_minidump(&data).unwrap());
+    scan(&MemoryDump::parse(&data).unwrap());
+}
+
+#[test]
+fn overlapping_regions() {
+    let mut stack = vec![0xccu8; 0x100];
+    stack[0x10..0x1c].copy_from_slice(&STRUCTURE);
+    stack[0x80..0x8c].copy_from_slice(&STRUCTURE);
+
+    // a second copy of part of the stack, as minidumps don't forbid overlapping ranges
+    let memory = [
+        (0x7ffd_0000_0000, stack.clone()),
+        (0x7ffd_0000_0008, stack[0x8..0x40].to_vec()),
+    ];
+    let data = build_minidump(&memory);
+    let dump = MemoryDump::parse(&data).unwrap();
+
+    assert_eq!(
+        dump.find_all(&Scanner::new(PATTERN)),
+        [0x7ffd_0000_0014, 0x7ffd_0000_0084]
+    );
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        MemoryDump::parse(b"MZ\x90\x00").unwrap_err(),
+        DumpError::UnknownFormat
+    );
+    assert_eq!(
+        MemoryDump::parse_core(b"MDMP").unwrap_err(),
+        DumpError::Elf(ElfError::BadMagic)
+    );
+
+    let mut data = build_core();
+    data[0x10] = 2;
+    assert_eq!(
+        MemoryDump::parse(&data).unwrap_err(),
+        DumpError::NotCoreFile(2)
+    );
+
+    let data = build_minidump(&memory());
+    assert_eq!(
+        MemoryDump::parse(&data[..0x1100]).unwrap_err(),
+        DumpError::Truncated
+    );
+    assert_eq!(
+        MemoryDump::parse(&data[..0x30]).unwrap_err(),
+        DumpError::Truncated
+    );
+}
@@ -0,0 +1,201 @@
+mod common;
+
+use common::Writer;
+use lightningscanner::elf::{Elf, ElfError};
+use lightningscanner::Scanner;
+
+const CODE: [u8; 16] = [
+    0x48, 0x89, 0x5c, 0x24, 0x08, 0x57, 0x48, 0x83, 0xec, 0x20, 0x8b, 0x05, 0x7a, 0x3c, 0x91, 0xe4,
+];
+
+const TEXT_OFFSET: usize = 0x100;
+const RODATA_OFFSET: usize = 0x200;
+const NAMES_OFFSET: usize = 0x300;
+const SECTION_HEADERS: usize = 0x400;
+const TEXT_ADDRESS: u64 = 0x401100;
+
+/// Build an ELF file with `.text` and `.rodata` sections, both containing `CODE`,
+/// and a single executable load segment covering `.text`
+fn build(is_64: bool, big_endian: bool) -> Vec<u8> {
+    let mut w = Writer::new(0x600, big_endian);
+    let word_size = if is_64 { 8 } else { 4 };
+
+    w.data[..4].copy_from_slice(b"\x7fELF");
+    w.data[4] = if is_64 { 2 } else { 1 };
+    w.data[5] = if big_endian { 2 } else { 1 };
+    w.data[6] = 1;
+
+    w.data[TEXT_OFFSET + 0x10..TEXT_OFFSET + 0x20].copy_from_slice(&CODE);
+    w.data[RODATA_OFFSET + 0x20..RODATA_OFFSET + 0x30].copy_from_slice(&CODE);
+
+    let names = b"\0.text\0.rodata\0.shstrtab\0";
+    w.data[NAMES_OFFSET..NAMES_OFFSET + names.len()].copy_from_slice(names);
+
+    // file header
+    let program_headers = if is_64 { 0x40 } else { 0x34 };
+    let (program_header_size, section_header_size) =
+        if is_64 { (0x38, 0x40) } else { (0x20, 0x28) };
+    w.put(0x10, 2, 2);
+    w.put(0x12, 0x3e, 2);
+    w.put(0x18, TEXT_ADDRESS, word_size);
+    let sizes = if is_64 {
+        w.put(0x20, program_headers, 8);
+        w.put(0x28, SECTION_HEADERS as u64, 8);
+        0x36
+    } else {
+        w.put(0x1c, program_headers, 4);
+        w.put(0x20, SECTION_HEADERS as u64, 4);
+        0x2a
+    };
+    w.put(sizes, program_header_size, 2);
+    w.put(sizes + 2, 1, 2);
+    w.put(sizes + 4, section_header_size, 2);
+    w.put(sizes + 6, 4, 2);
+    w.put(sizes + 8, 3, 2);
+
+    // executable load segment
+    let header = program_headers as usize;
+    w.put(header, 1, 4);
+    if is_64 {
+        w.put(header + 0x4, 0x5, 4);
+        w.put(header + 0x8, TEXT_OFFSET as u64, 8);
+        w.put(header + 0x10, TEXT_ADDRESS, 8);
+        w.put(header + 0x20, 0x100, 8);
+        w.put(header + 0x28, 0x100, 8);
+    } else {
+        w.put(header + 0x4, TEXT_OFFSET as u64, 4);
+        w.put(header + 0x8, TEXT_ADDRESS, 4);
+        w.put(header + 0x10, 0x100, 4);
+        w.put(header + 0x14, 0x100, 4);
+        w.put(header + 0x18, 0x5, 4);
+    }
+
+    // sections
+    let sections = [
+        (1, 1, 0x6, TEXT_ADDRESS, TEXT_OFFSET, 0x100),
+        (7, 1, 0x2, 0, RODATA_OFFSET, 0x100),
+        (15, 3, 0, 0, NAMES_OFFSET, names.len()),
+    ];
+    for (index, (name, kind, flags, address, offset, size)) in sections.into_iter().enumerate() {
+        let header = SECTION_HEADERS + (index + 1) * section_header_size as usize;
+        w.put(header, name, 4);
+        w.put(header + 0x4, kind, 4);
+        w.put(header + 0x8, flags, word_size);
+        if is_64 {
+            w.put(header + 0x10, address, 8);
+            w.put(header + 0x18, offset as u64, 8);
+            w.put(header + 0x20, size as u64, 8);
+        } else {
+            w.put(header + 0xc, address, 4);
+            w.put(header + 0x10, offset as u64, 4);
+            w.put(header + 0x14, size as u64, 4);
+        }
+    }
+
+    w.data
+}
+
+fn parse_and_scan(is_64: bool, big_endian: bool) {
+    let data = build(is_64, big_endian);
+    let elf = Elf::parse(&data).unwrap();
+
+    assert_eq!(elf.is_64(), is_64);
+    assert_eq!(elf.is_big_endian(), big_endian);
+    assert_eq!(elf.machine(), 0x3e);
+    assert_eq!(elf.entry(), TEXT_ADDRESS);
+
+    let names = elf
+        .sections()
+        .iter()
+        .map(|section| section.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["", ".text", ".rodata", ".shstrtab"]);
+
+    let text = elf.section(".text").unwrap();
+    assert!(text.is_executable() && text.is_alloc() && !text.is_writable());
+    assert_eq!(elf.section_data(text).len(), 0x100);
+
+    let scanner = Scanner::new("48 89 5c 24 ?? 57 48 83 ec 20 8b 05 & 7a 3c 91 e4");
+
+    let result = elf.find_in_section(&scanner, ".text").unwrap();
+    assert_eq!(result.offset(), TEXT_OFFSET + 0x1c);
+    assert_eq!(
+        elf.offset_to_address(result.offset()),
+        Some(TEXT_ADDRESS + 0x1c)
+    );
+    assert_eq!(
+        elf.address_to_offset(TEXT_ADDRESS + 0x1c),
+        Some(result.offset())
+    );
+
+    let result = elf.find_in_section(&scanner, ".rodata").unwrap();
+    assert_eq!(result.offset(), RODATA_OFFSET + 0x2c);
+    assert_eq!(elf.offset_to_address(result.offset()), None);
+
+    let results = elf.find_all_in_executable_segments(&scanner);
+    assert_eq!(results.len(), 1);
+    assert_eq!(results[0].offset(), TEXT_OFFSET + 0x1c);
+    assert_eq!(elf.find_in_executable_segments(&scanner), Some(results[0]));
+
+    assert!(elf.find_in_section(&scanner, ".data").is_none());
+    assert!(elf.find_all_in_section(&scanner, ".shstrtab").is_empty());
+}
+
+#[test]
+fn elf64_little_endian() {
+    parse_and_scan(true, false);
+}
+
+#[test]
+fn elf64_big_endian() {
+    parse_and_scan(true, true);
+}
+
+#[test]
+fn elf32_little_endian() {
+    parse_and_scan(false, false);
+}
+
+#[test]
+fn elf32_big_endian() {
+    parse_and_scan(false, true);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(Elf::parse(b"MZ\x90\x00").unwrap_err(), ElfError::BadMagic);
+    assert_eq!(
+        Elf::parse(b"\x7fELF\x03\x01").unwrap_err(),
+        ElfError::UnsupportedClass(3)
+    );
+    assert_eq!(
+        Elf::parse(b"\x7fELF\x02\x00").unwrap_err(),
+        ElfError::UnsupportedEncoding(0)
+    );
+    assert_eq!(
+        Elf::parse(&build(true, false)[..0x420]).unwrap_err(),
+        ElfError::Truncated
+    );
+
+    let mut data = build(true, false);
+    data.truncate(0x2f0);
+    assert_eq!(Elf::parse(&data).unwrap_err(), ElfError::Truncated);
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn current_executable() {
+    let data = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    let elf = Elf::parse(&data).unwrap();
+
+    assert!(elf.is_64());
+    let text = elf.section(".text").unwrap();
+    assert!(text.is_executable());
+    assert!(elf.load_segments().any(|segment| segment.is_executable()
+        && segment
+            .file_range()
+            .contains(&text.file_range().unwrap().start)));
+
+    let address = elf.offset_to_address(text.file_range().unwrap().start);
+    assert_eq!(address, Some(text.address()));
+}
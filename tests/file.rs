@@ -0,0 +1,67 @@
+#![cfg(feature = "mmap")]
+
+use lightningscanner::Scanner;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+/// Temporary file removed on drop
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(name: &str, contents: &[u8]) -> Self {
+        let path = std::env::temp_dir().join(format!(
+            "lightningscanner-{}-{name}.bin",
+            std::process::id()
+        ));
+        fs::write(&path, contents).unwrap();
+
+        TempFile(path)
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn find_in_file() {
+    let mut contents = vec![0xcc; 0x10000];
+    contents[0x1234..0x123c].copy_from_slice(&[0x48, 0x89, 0x5c, 0x24, 0x08, 0x48, 0x89, 0x6c]);
+    contents[0xfff8..].copy_from_slice(&[0x48, 0x89, 0x5c, 0x24, 0x10, 0x48, 0x89, 0x6c]);
+    let file = TempFile::new("find", &contents);
+
+    let scanner = Scanner::new("48 89 5c 24 ?? 48 89 6c");
+    assert_eq!(scanner.find_in_file(&file.0).unwrap(), Some(0x1234));
+    assert_eq!(scanner.find_all_in_file(&file.0).unwrap(), [0x1234, 0xfff8]);
+
+    let scanner = Scanner::new("48 89 5c 24 & ?? 48 89 6c");
+    assert_eq!(scanner.find_in_file(&file.0).unwrap(), Some(0x1238));
+}
+
+#[test]
+fn no_match() {
+    let file = TempFile::new("no-match", &[0xcc; 0x100]);
+
+    let scanner = Scanner::new("48 89 5c 24 ?? 48 89 6c");
+    assert_eq!(scanner.find_in_file(&file.0).unwrap(), None);
+    assert!(scanner.find_all_in_file(&file.0).unwrap().is_empty());
+}
+
+#[test]
+fn empty_file() {
+    let file = TempFile::new("empty", &[]);
+
+    let scanner = Scanner::new("48 89");
+    assert_eq!(scanner.find_in_file(&file.0).unwrap(), None);
+}
+
+#[test]
+fn missing_file() {
+    let path = std::env::temp_dir().join("lightningscanner-missing-file.bin");
+
+    let err = Scanner::new("48 89").find_in_file(path).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+}
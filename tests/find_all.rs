@@ -0,0 +1,75 @@
+use lightningscanner::{ScanMode, Scanner};
+
+const PATTERN: &str = "48 89 5c 24 ?? 48";
+
+const DATA_SET: [u8; 80] = [
+    0x48, 0x89, 0x5c, 0x24, 0x08, 0x48, 0x89, 0x6c, 0x24, 0x10, 0x48, 0x89, 0x74, 0x24, 0x18, 0x57,
+    0x48, 0x83, 0xec, 0x20, 0x48, 0x89, 0x5c, 0x24, 0x48, 0x89, 0x5c, 0x24, 0x48, 0x48, 0x8b, 0xd9,
+    0xe8, 0x10, 0x00, 0x00, 0x00, 0x48, 0x8b, 0xcb, 0xe8, 0x20, 0x00, 0x00, 0x00, 0x48, 0x89, 0x5c,
+    0x24, 0x30, 0x48, 0x89, 0x5c, 0x24, 0x38, 0x48, 0x83, 0xc4, 0x20, 0x5f, 0xc3, 0xcc, 0xcc, 0xcc,
+    0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0x48, 0x89, 0x5c, 0x24, 0x00,
+];
+
+const EXPECTED_FIND: [usize; 4] = [0x00, 0x18, 0x2d, 0x32];
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn avx2() {
+    let scanner = Scanner::new(PATTERN);
+    let offsets = scanner
+        .find_all_in_with_mode(Some(ScanMode::Avx2), &DATA_SET)
+        .map(|result| result.offset())
+        .collect::<Vec<_>>();
+
+    assert_eq!(offsets, EXPECTED_FIND);
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn sse42() {
+    let scanner = Scanner::new(PATTERN);
+    let offsets = scanner
+        .find_all_in_with_mode(Some(ScanMode::Sse42), &DATA_SET)
+        .map(|result| result.offset())
+        .collect::<Vec<_>>();
+
+    assert_eq!(offsets, EXPECTED_FIND);
+}
+
+#[test]
+fn scalar() {
+    let scanner = Scanner::new(PATTERN);
+    let offsets = scanner
+        .find_all_in_with_mode(Some(ScanMode::Scalar), &DATA_SET)
+        .map(|result| result.offset())
+        .collect::<Vec<_>>();
+
+    assert_eq!(offsets, EXPECTED_FIND);
+}
+
+#[test]
+fn overlapping() {
+    let data_set = [0xcc; 8];
+
+    let scanner = Scanner::new("cc cc cc");
+    let offsets = scanner
+        .find_all_in(&data_set)
+        .map(|result| result.offset())
+        .collect::<Vec<_>>();
+
+    assert_eq!(offsets, [0, 1, 2, 3, 4, 5]);
+}
+
+#[test]
+fn raw_pointers() {
+    let scanner = Scanner::new(PATTERN);
+    // SAFETY: DATA_SET is a valid slice
+    let results = unsafe { scanner.find_all(None, DATA_SET.as_ptr(), DATA_SET.len()) };
+
+    let data_set_addr = DATA_SET.as_ptr() as usize;
+    let offsets = results
+        .map(|result| result.get_addr() as usize - data_set_addr)
+        .collect::<Vec<_>>();
+
+    assert_eq!(offsets, EXPECTED_FIND);
+}
@@ -0,0 +1,104 @@
+use lightningscanner::{ScanMode, Scanner};
+
+const DATA_SET: [u8; 48] = [
+    0x48, 0x89, 0x5c, 0x24, 0x08, 0x57, 0x48, 0x83, 0xec, 0x20, 0x48, 0x8b, 0xd9, 0xe8, 0x10, 0x00,
+    0x00, 0x00, 0x48, 0x8b, 0xcb, 0xe8, 0x20, 0x00, 0x00, 0x00, 0x48, 0x89, 0x5c, 0x24, 0x30, 0x48,
+    0x83, 0xc4, 0x20, 0x5f, 0xc3, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+];
+
+fn find_unique(scan_mode: Option<ScanMode>) {
+    let scanner = Scanner::new("48 8b cb e8 & ?? ?? ?? ??");
+    let result = scanner.find_unique_with_mode(scan_mode, &DATA_SET).unwrap();
+    assert_eq!(result.offset(), 0x16);
+
+    let err = Scanner::new("de ad")
+        .find_unique_with_mode(scan_mode, &DATA_SET)
+        .unwrap_err();
+    assert_eq!(err.count(), 0);
+    assert!(err.offsets().is_empty());
+
+    let err = Scanner::new("e8 ?? 00 00 00")
+        .find_unique_with_mode(scan_mode, &DATA_SET)
+        .unwrap_err();
+    assert_eq!(err.count(), 2);
+    assert_eq!(err.offsets(), [0x0d, 0x15]);
+
+    // only the first few offsets are reported
+    let err = Scanner::new("cc")
+        .find_unique_with_mode(scan_mode, &DATA_SET)
+        .unwrap_err();
+    assert_eq!(err.count(), 11);
+    assert_eq!(err.offsets(), (0x25..0x2d).collect::<Vec<_>>());
+}
+
+fn count(scan_mode: Option<ScanMode>) {
+    for pattern in [
+        "48",
+        "48 89 5c 24",
+        "e8 ?? 00",
+        "cc cc",
+        "cc cc cc cc cc cc cc cc cc cc cc cc",
+        "de ad",
+    ] {
+        let scanner = Scanner::new(pattern);
+
+        assert_eq!(
+            scanner.count_with_mode(scan_mode, &DATA_SET),
+            scanner.find_all_in_with_mode(scan_mode, &DATA_SET).count(),
+            "{pattern}"
+        );
+    }
+
+    assert_eq!(
+        Scanner::new("cc cc").count_with_mode(scan_mode, &DATA_SET),
+        10
+    );
+    assert_eq!(Scanner::new("48").count_with_mode(scan_mode, &[]), 0);
+}
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn avx2() {
+    find_unique(Some(ScanMode::Avx2));
+    count(Some(ScanMode::Avx2));
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn sse42() {
+    find_unique(Some(ScanMode::Sse42));
+    count(Some(ScanMode::Sse42));
+}
+
+#[test]
+fn scalar() {
+    find_unique(Some(ScanMode::Scalar));
+    count(Some(ScanMode::Scalar));
+}
+
+#[test]
+fn runtime_detected() {
+    find_unique(None);
+    count(None);
+
+    let scanner = Scanner::new("48 89 5c 24");
+    assert_eq!(scanner.count(&DATA_SET), 2);
+    assert!(scanner.find_unique(&DATA_SET).is_err());
+}
+
+#[test]
+fn error_message() {
+    let err = Scanner::new("de ad").find_unique(&DATA_SET).unwrap_err();
+    assert_eq!(err.to_string(), "pattern not found");
+
+    let err = Scanner::new("48 89 5c 24")
+        .find_unique(&DATA_SET)
+        .unwrap_err();
+    assert_eq!(err.to_string(), "pattern found 2 times, at 0x0, 0x1a");
+
+    let err = Scanner::new("cc").find_unique(&DATA_SET).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "pattern found 11 times, at 0x25, 0x26, 0x27, 0x28, 0x29, 0x2a, 0x2b, 0x2c, ..."
+    );
+}
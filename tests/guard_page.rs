@@ -0,0 +1,125 @@
+#![cfg(unix)]
+
+use lightningscanner::pattern::Pattern;
+use lightningscanner::{MultiScanner, ScanMode, Scanner};
+use std::{ptr, slice};
+
+const PATTERN: &str = "48 89 5c 24 ?? 48 89 6c 24 ?? 48 89 74 24 ?? 57 41 56 41 57 48 83 ec 20 4c 8b f9 48 8b ?? ?? ?? 8b";
+
+/// A readable page followed by an inaccessible guard page
+struct GuardedPage {
+    page: *mut u8,
+    page_size: usize,
+}
+
+impl GuardedPage {
+    fn new() -> Self {
+        // SAFETY: sysconf is always safe to call
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+
+        // SAFETY: mapping new anonymous memory doesn't affect existing memory
+        let page = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                page_size * 2,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        };
+        assert_ne!(page, libc::MAP_FAILED);
+
+        let page = page as *mut u8;
+        // SAFETY: the second page belongs to the mapping created above
+        let result =
+            unsafe { libc::mprotect(page.add(page_size) as *mut _, page_size, libc::PROT_NONE) };
+        assert_eq!(result, 0);
+
+        GuardedPage { page, page_size }
+    }
+
+    /// Get the last `len` bytes before the guard page
+    fn tail(&mut self, len: usize) -> &mut [u8] {
+        // SAFETY: the first page is readable and writable
+        unsafe { slice::from_raw_parts_mut(self.page.add(self.page_size - len), len) }
+    }
+}
+
+impl Drop for GuardedPage {
+    fn drop(&mut self) {
+        // SAFETY: the mapping was created in `GuardedPage::new`
+        unsafe { libc::munmap(self.page as *mut _, self.page_size * 2) };
+    }
+}
+
+fn scan_tails(scan_mode: ScanMode) {
+    let pattern = Pattern::new(PATTERN);
+    let scanner = Scanner::from(pattern.clone());
+    let multi_scanner = [pattern, Pattern::new("cc cc 8b")]
+        .into_iter()
+        .collect::<MultiScanner>();
+
+    let mut page = GuardedPage::new();
+
+    for len in 0..=96 {
+        let haystack = page.tail(len);
+        haystack.fill(0xcc);
+
+        assert!(scanner
+            .find_in_with_mode(Some(scan_mode), haystack)
+            .is_none());
+        assert_eq!(
+            scanner
+                .find_all_in_with_mode(Some(scan_mode), haystack)
+                .count(),
+            0
+        );
+        assert!(multi_scanner.find_in_with_mode(Some(scan_mode), haystack)[0].is_none());
+
+        // a match truncated by the end of the haystack
+        if len >= 33 {
+            let bytes = Pattern::new(PATTERN).to_code_style().0;
+            let bytes = bytes
+                .split("\\x")
+                .skip(1)
+                .map(|byte| u8::from_str_radix(byte, 16).unwrap())
+                .collect::<Vec<_>>();
+
+            haystack[len - 32..].copy_from_slice(&bytes[..32]);
+            assert!(scanner
+                .find_in_with_mode(Some(scan_mode), haystack)
+                .is_none());
+
+            haystack[len - 33..].copy_from_slice(&bytes);
+            let result = scanner.find_in_with_mode(Some(scan_mode), haystack);
+            assert_eq!(result.unwrap().offset(), len - 33);
+        }
+    }
+}
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn avx2() {
+    scan_tails(ScanMode::Avx2);
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn sse42() {
+    scan_tails(ScanMode::Sse42);
+}
+
+#[test]
+fn scalar() {
+    scan_tails(ScanMode::Scalar);
+}
+
+#[test]
+fn empty_pattern() {
+    let scanner = Scanner::from(Pattern::from_bytes(&[]));
+    let mut page = GuardedPage::new();
+
+    assert_eq!(scanner.find_in(page.tail(0)).unwrap().offset(), 0);
+    assert_eq!(scanner.find_in(page.tail(4)).unwrap().offset(), 0);
+}
@@ -0,0 +1,258 @@
+mod common;
+
+use common::Writer;
+use lightningscanner::macho::{MachO, MachOError, CPU_TYPE_ARM64, CPU_TYPE_X86_64};
+use lightningscanner::pattern::Pattern;
+use lightningscanner::Scanner;
+
+const X86_64_CODE: [u8; 12] = [
+    0x55, 0x48, 0x89, 0xe5, 0x48, 0x8d, 0x3d, 0x11, 0x22, 0x33, 0x44, 0x5d,
+];
+const ARM64_CODE: [u8; 12] = [
+    0xfd, 0x7b, 0xbf, 0xa9, 0xfd, 0x03, 0x00, 0x91, 0xfd, 0x7b, 0xc1, 0xa8,
+];
+
+const IMAGE_SIZE: usize = 0x1000;
+const TEXT_OFFSET: usize = 0x400;
+const CSTRING_OFFSET: usize = 0x600;
+const DATA_OFFSET: usize = 0x800;
+
+/// Segment name, vmaddr, file offset, file size, protection and sections,
+/// as section name, address offset, file offset, size and flags
+type SegmentLayout = (
+    &'static str,
+    u64,
+    usize,
+    usize,
+    u64,
+    Vec<(&'static str, u64, usize, u64, u64)>,
+);
+
+/// Build a thin image with a `__TEXT` segment containing `__text` and `__cstring`,
+/// and a `__DATA` segment containing `__data` and a zero-filled `__bss`
+fn build_image(is_64: bool, big_endian: bool, cpu_type: u32, code: &[u8]) -> Vec<u8> {
+    let mut w = Writer::new(IMAGE_SIZE, big_endian);
+
+    let base = if is_64 { 0x1_0000_0000 } else { 0x1000 };
+    let segments: [SegmentLayout; 2] = [
+        (
+            "__TEXT",
+            base,
+            0,
+            DATA_OFFSET,
+            0x5,
+            vec![
+                ("__text", TEXT_OFFSET as u64, TEXT_OFFSET, 0x40, 0x8000_0400),
+                (
+                    "__cstring",
+                    CSTRING_OFFSET as u64,
+                    CSTRING_OFFSET,
+                    0x20,
+                    0x2,
+                ),
+            ],
+        ),
+        (
+            "__DATA",
+            base + 0x4000,
+            DATA_OFFSET,
+            IMAGE_SIZE - DATA_OFFSET,
+            0x3,
+            vec![
+                ("__data", 0, DATA_OFFSET, 0x10, 0x0),
+                ("__bss", 0x10, 0, 0x100, 0x1),
+            ],
+        ),
+    ];
+
+    let magic = if is_64 { 0xfeed_facf } else { 0xfeed_face };
+    w.put(0, magic, 4);
+    w.put(0x4, cpu_type as u64, 4);
+    w.put(0xc, 0x2, 4);
+    w.put(0x10, segments.len() as u64, 4);
+
+    let (word_size, header_size, section_size) = if is_64 {
+        (8, 0x48, 0x50)
+    } else {
+        (4, 0x38, 0x44)
+    };
+    let mut command = if is_64 { 0x20 } else { 0x1c };
+    for (name, vmaddr, offset, file_size, protection, sections) in segments {
+        let size = header_size + sections.len() * section_size;
+
+        w.put(command, if is_64 { 0x19 } else { 0x1 }, 4);
+        w.put(command + 0x4, size as u64, 4);
+        w.bytes(command + 0x8, name.as_bytes());
+
+        let fields = command + 0x18;
+        w.put(fields, vmaddr, word_size);
+        w.put(fields + word_size, 0x4000, word_size);
+        w.put(fields + 2 * word_size, offset as u64, word_size);
+        w.put(fields + 3 * word_size, file_size as u64, word_size);
+        w.put(fields + 4 * word_size, 0x7, 4);
+        w.put(fields + 4 * word_size + 0x4, protection, 4);
+        w.put(fields + 4 * word_size + 0x8, sections.len() as u64, 4);
+
+        for (index, (section_name, address, section_offset, section_size_bytes, flags)) in
+            sections.into_iter().enumerate()
+        {
+            let section = command + header_size + index * section_size;
+            let fields = section + 0x20;
+
+            w.bytes(section, section_name.as_bytes());
+            w.bytes(section + 0x10, name.as_bytes());
+            w.put(fields, vmaddr + address, word_size);
+            w.put(fields + word_size, section_size_bytes, word_size);
+            w.put(fields + 2 * word_size, section_offset as u64, 4);
+            w.put(fields + 2 * word_size + 0x10, flags, 4);
+        }
+
+        command += size;
+    }
+
+    w.data[TEXT_OFFSET + 0x8..TEXT_OFFSET + 0x8 + code.len()].copy_from_slice(code);
+    w.data[CSTRING_OFFSET..CSTRING_OFFSET + code.len()].copy_from_slice(code);
+
+    w.data
+}
+
+/// Build a fat file with an x86-64 slice at 0x1000 and an arm64 slice at 0x2000
+fn build_fat(is_64: bool) -> Vec<u8> {
+    let mut w = Writer::new(0x1000, true);
+
+    w.put(0, if is_64 { 0xcafe_babf } else { 0xcafe_babe }, 4);
+    w.put(0x4, 2, 4);
+
+    let slices = [(CPU_TYPE_X86_64, 0x1000), (CPU_TYPE_ARM64, 0x2000)];
+    for (index, (cpu_type, offset)) in slices.into_iter().enumerate() {
+        if is_64 {
+            let entry = 0x8 + index * 0x20;
+            w.put(entry, cpu_type as u64, 4);
+            w.put(entry + 0x8, offset, 8);
+            w.put(entry + 0x10, IMAGE_SIZE as u64, 8);
+        } else {
+            let entry = 0x8 + index * 0x14;
+            w.put(entry, cpu_type as u64, 4);
+            w.put(entry + 0x8, offset, 4);
+            w.put(entry + 0xc, IMAGE_SIZE as u64, 4);
+        }
+    }
+
+    let mut data = w.data;
+    data.extend(build_image(true, false, CPU_TYPE_X86_64, &X86_64_CODE));
+    data.extend(build_image(true, false, CPU_TYPE_ARM64, &ARM64_CODE));
+    data
+}
+
+fn check_image(macho: &MachO, code: &[u8], slice_offset: usize) {
+    let base = if macho.is_64() { 0x1_0000_0000 } else { 0x1000 };
+
+    let text = macho.section("__TEXT", "__text").unwrap();
+    assert!(text.is_executable());
+    assert_eq!(text.address(), base + TEXT_OFFSET as u64);
+    assert_eq!(macho.section_data(text).len(), 0x40);
+    assert!(macho.segment("__TEXT").unwrap().is_executable());
+    assert!(macho.segment("__DATA").unwrap().is_writable());
+
+    let bss = macho.section("__DATA", "__bss").unwrap();
+    assert!(bss.is_zerofill());
+    assert!(bss.file_range().is_none());
+
+    let scanner = Scanner::from(Pattern::from_bytes(code));
+
+    let result = macho.find_in_section(&scanner, "__TEXT", "__text").unwrap();
+    assert_eq!(result.offset(), slice_offset + TEXT_OFFSET + 0x8);
+    assert_eq!(
+        macho.offset_to_vmaddr(result.offset()),
+        Some(base + TEXT_OFFSET as u64 + 0x8)
+    );
+    assert_eq!(
+        macho.vmaddr_to_offset(base + TEXT_OFFSET as u64 + 0x8),
+        Some(result.offset())
+    );
+
+    let result = macho
+        .find_in_section(&scanner, "__TEXT", "__cstring")
+        .unwrap();
+    assert_eq!(result.offset(), slice_offset + CSTRING_OFFSET);
+
+    let results = macho.find_all_in_executable_sections(&scanner);
+    assert_eq!(results.len(), 1);
+    assert_eq!(
+        macho.find_in_executable_sections(&scanner),
+        Some(results[0])
+    );
+
+    assert!(macho.find_in_section(&scanner, "__DATA", "__bss").is_none());
+    assert!(macho
+        .find_all_in_section(&scanner, "__DATA", "__data")
+        .is_empty());
+    assert_eq!(macho.vmaddr_to_offset(base + 0x4000 + 0x900), None);
+}
+
+#[test]
+fn thin_64_little_endian() {
+    let data = build_image(true, false, CPU_TYPE_X86_64, &X86_64_CODE);
+    let macho = MachO::parse(&data).unwrap();
+
+    assert!(macho.is_64() && !macho.is_big_endian());
+    assert_eq!(macho.cpu_type(), CPU_TYPE_X86_64);
+    assert_eq!(macho.file_type(), 0x2);
+    assert_eq!(macho.slice_range(), 0..IMAGE_SIZE);
+    check_image(&macho, &X86_64_CODE, 0);
+}
+
+#[test]
+fn thin_32_big_endian() {
+    let data = build_image(false, true, 0x12, &X86_64_CODE);
+    let macho = MachO::parse(&data).unwrap();
+
+    assert!(!macho.is_64() && macho.is_big_endian());
+    check_image(&macho, &X86_64_CODE, 0);
+}
+
+#[test]
+fn fat() {
+    for is_64 in [false, true] {
+        let data = build_fat(is_64);
+
+        let slices = MachO::parse_all(&data).unwrap();
+        assert_eq!(slices.len(), 2);
+
+        let x86_64 = MachO::parse_arch(&data, CPU_TYPE_X86_64).unwrap();
+        assert_eq!(x86_64.slice_range(), 0x1000..0x2000);
+        check_image(&x86_64, &X86_64_CODE, 0x1000);
+
+        let arm64 = MachO::parse_arch(&data, CPU_TYPE_ARM64).unwrap();
+        assert_eq!(arm64.slice_range(), 0x2000..0x3000);
+        check_image(&arm64, &ARM64_CODE, 0x2000);
+
+        // code of the other slice is not found in the selected one
+        let scanner = Scanner::from(Pattern::from_bytes(&X86_64_CODE));
+        assert!(arm64
+            .find_in_section(&scanner, "__TEXT", "__text")
+            .is_none());
+    }
+}
+
+#[test]
+fn errors() {
+    assert_eq!(MachO::parse(b"\x7fELF").unwrap_err(), MachOError::BadMagic);
+    assert_eq!(MachO::parse(b"MZ").unwrap_err(), MachOError::BadMagic);
+
+    let data = build_image(true, false, CPU_TYPE_X86_64, &X86_64_CODE);
+    assert_eq!(
+        MachO::parse_arch(&data, CPU_TYPE_ARM64).unwrap_err(),
+        MachOError::ArchNotFound(CPU_TYPE_ARM64)
+    );
+    assert_eq!(
+        MachO::parse(&data[..0x100]).unwrap_err(),
+        MachOError::Truncated
+    );
+
+    let data = build_fat(false);
+    assert_eq!(
+        MachO::parse_all(&data[..0x2800]).unwrap_err(),
+        MachOError::Truncated
+    );
+}
@@ -0,0 +1,67 @@
+#![cfg(target_os = "linux")]
+
+use lightningscanner::module::Module;
+use lightningscanner::Scanner;
+
+/// Known bytes placed in the executable segment of the test binary
+#[link_section = ".text"]
+#[used]
+static CODE: [u8; 16] = [
+    0x48, 0x89, 0x5c, 0x24, 0x08, 0x57, 0x48, 0x83, 0xec, 0x20, 0x8b, 0x05, 0x7a, 0x3c, 0x91, 0xe4,
+];
+
+#[test]
+fn main_program() {
+    let module = Module::main();
+    let code = CODE.as_ptr() as usize;
+
+    assert_eq!(module.name(), "");
+    assert!(module.range().contains(&code));
+    assert!(module
+        .executable_segments()
+        .any(|segment| segment.range().contains(&code)));
+
+    let scanner = Scanner::new("48 89 5c 24 ?? 57 48 83 ec 20 8b 05 7a 3c 91 e4");
+    let result = scanner.find_in_module(&module);
+    assert_eq!(result.get_addr() as usize, code);
+
+    let scanner = Scanner::new("48 89 5c 24 ?? 57 48 83 ec 20 8b 05 & 7a 3c 91 e4");
+    let result = scanner.find_in_module(&module);
+    assert_eq!(result.get_addr() as usize, code + 12);
+}
+
+#[test]
+fn shared_library() {
+    let libc = Module::find("libc.so.6").unwrap();
+    let getpid = libc::getpid as *const () as usize;
+
+    assert!(libc.name().ends_with("libc.so.6"));
+    assert!(libc.range().contains(&getpid));
+    assert!(libc
+        .executable_segments()
+        .any(|segment| segment.range().contains(&getpid)));
+    assert!(libc
+        .segments()
+        .iter()
+        .all(|segment| libc.range().contains(&segment.start())));
+
+    let by_path = Module::find(libc.name()).unwrap();
+    assert_eq!(by_path.base(), libc.base());
+    assert_eq!(by_path.size(), libc.size());
+}
+
+#[test]
+fn missing_module() {
+    assert!(Module::find("libdoesnotexist.so").is_none());
+}
+
+#[test]
+fn pattern_not_in_executable_segments() {
+    static DATA: [u8; 12] = [
+        0x9d, 0x17, 0xc2, 0x6e, 0x04, 0xb8, 0x3f, 0x51, 0xaa, 0x28, 0xe6, 0x73,
+    ];
+
+    let scanner = Scanner::new("9d 17 c2 6e 04 b8 3f 51 aa 28 e6 73");
+    assert!(!scanner.find_in_module(&Module::main()).is_valid());
+    assert_eq!(DATA[0], 0x9d);
+}
@@ -0,0 +1,153 @@
+use lightningscanner::pattern::Pattern;
+use lightningscanner::{MultiScanner, ScanMode, Scanner};
+use tinyrand::{Rand, Wyrand};
+
+const PATTERNS: [&str; 6] = [
+    "48 89 5c 24 ?? 48 89 6c",
+    "?? 8b 05 & ?? ?? ?? ??",
+    "e8 ?? ?? ?? ?? 48 8b c8",
+    "4? ?5 ?0",
+    "cc cc cc cc cc cc cc cc",
+    "9e 87 00",
+];
+
+const DATA_SET: [u8; 96] = [
+    0xdb, 0x2f, 0x16, 0x37, 0xd5, 0xff, 0x12, 0x74, 0x7c, 0xf2, 0x27, 0xed, 0x7b, 0x2e, 0x54, 0x9a,
+    0xe2, 0xec, 0x73, 0x9e, 0xbb, 0xd1, 0x42, 0xc2, 0x0c, 0x9e, 0xa3, 0xa1, 0x10, 0xb3, 0x97, 0xf2,
+    0xaf, 0x47, 0x43, 0x9f, 0xa0, 0x9e, 0x87, 0x00, 0x76, 0x5c, 0x3a, 0xae, 0x40, 0x30, 0x7f, 0xc0,
+    0x53, 0x48, 0x89, 0x5c, 0x24, 0x08, 0x48, 0x89, 0x6c, 0x24, 0x10, 0xe8, 0x10, 0x20, 0x30, 0x40,
+    0x48, 0x8b, 0xc8, 0x48, 0x8b, 0x05, 0x01, 0x02, 0x03, 0x04, 0x48, 0x85, 0xc0, 0x74, 0x05, 0xcc,
+    0x4d, 0x2b, 0xf6, 0x2f, 0x9e, 0x03, 0x5f, 0x56, 0x02, 0x2e, 0x5f, 0x58, 0x9c, 0x6d, 0xa3, 0xf5,
+];
+
+const EXPECTED_FIND: [Option<usize>; 6] = [
+    Some(0x31),
+    Some(0x46),
+    Some(0x3b),
+    Some(0x4a),
+    None,
+    Some(0x25),
+];
+
+fn scanner() -> MultiScanner {
+    PATTERNS.into_iter().map(Pattern::new).collect()
+}
+
+fn offsets(scanner: &MultiScanner, scan_mode: ScanMode) -> Vec<Option<usize>> {
+    scanner
+        .find_in_with_mode(Some(scan_mode), &DATA_SET)
+        .into_iter()
+        .map(|result| result.map(|result| result.offset()))
+        .collect()
+}
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn avx2() {
+    assert_eq!(offsets(&scanner(), ScanMode::Avx2), EXPECTED_FIND);
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn sse42() {
+    assert_eq!(offsets(&scanner(), ScanMode::Sse42), EXPECTED_FIND);
+}
+
+#[test]
+fn scalar() {
+    assert_eq!(offsets(&scanner(), ScanMode::Scalar), EXPECTED_FIND);
+}
+
+#[test]
+fn raw_pointers() {
+    let scanner = scanner();
+    // SAFETY: DATA_SET is a valid slice
+    let results = unsafe { scanner.find(None, DATA_SET.as_ptr(), DATA_SET.len()) };
+
+    let data_set_addr = DATA_SET.as_ptr() as usize;
+    let offsets = results
+        .into_iter()
+        .map(|result| {
+            result
+                .is_valid()
+                .then(|| result.get_addr() as usize - data_set_addr)
+        })
+        .collect::<Vec<_>>();
+
+    assert_eq!(offsets, EXPECTED_FIND);
+}
+
+#[test]
+fn same_as_single_scanner() {
+    let mut rand = Wyrand::default();
+
+    let mut data = vec![0u8; 0x10000];
+    for byte in data.iter_mut() {
+        // a small alphabet, so short patterns match repeatedly
+        *byte = (rand.next_u16() % 8) as u8;
+    }
+
+    let patterns = (0..64)
+        .map(|_| {
+            let len = 2 + rand.next_usize() % 6;
+            let bytes = (0..len)
+                .map(|_| (rand.next_u16() % 8) as u8)
+                .collect::<Vec<_>>();
+            let mask = (0..len)
+                .map(|_| [0x00, 0x0f, 0xff, 0xff][rand.next_usize() % 4])
+                .collect::<Vec<_>>();
+
+            Pattern::from_bytes_and_mask(&bytes, &mask)
+        })
+        .collect::<Vec<_>>();
+
+    let scanner = patterns.iter().cloned().collect::<MultiScanner>();
+    let results = scanner.find_in(&data);
+
+    for (pattern, result) in patterns.into_iter().zip(results) {
+        let expected = Scanner::from(pattern).find_in_with_mode(Some(ScanMode::Scalar), &data);
+        assert_eq!(
+            result.map(|result| result.offset()),
+            expected.map(|result| result.offset())
+        );
+    }
+}
+
+/// Found anchors are dropped from the prefilter, the remaining patterns must still be found
+fn shared_anchors(scan_mode: ScanMode) {
+    let scanner = ["aa bb", "aa cc", "dd", "ee", "dd ee ff"]
+        .into_iter()
+        .map(Pattern::new)
+        .collect::<MultiScanner>();
+
+    let mut data = vec![0u8; 128];
+    data[3..5].copy_from_slice(&[0xaa, 0xbb]);
+    data[6..8].copy_from_slice(&[0xdd, 0xee]);
+    data[100..103].copy_from_slice(&[0xdd, 0xee, 0xff]);
+    data[120..122].copy_from_slice(&[0xaa, 0xcc]);
+
+    let offsets = scanner
+        .find_in_with_mode(Some(scan_mode), &data)
+        .into_iter()
+        .map(|result| result.map(|result| result.offset()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(offsets, [Some(3), Some(120), Some(6), Some(7), Some(100)]);
+}
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn shared_anchors_avx2() {
+    shared_anchors(ScanMode::Avx2);
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn shared_anchors_sse42() {
+    shared_anchors(ScanMode::Sse42);
+}
+
+#[test]
+fn shared_anchors_scalar() {
+    shared_anchors(ScanMode::Scalar);
+}
@@ -0,0 +1,48 @@
+use lightningscanner::pattern::{Pattern, PatternErrorKind};
+use lightningscanner::{ScanMode, Scanner};
+
+const PATTERN: &str = "4? 8b ?5 ?? ?? ?? ?? 4? 85 c0";
+
+const DATA_SET: [u8; 64] = [
+    0x48, 0x8b, 0x0d, 0x10, 0x20, 0x30, 0x40, 0x48, 0x85, 0xc0, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+    0x58, 0x8b, 0x05, 0x10, 0x20, 0x30, 0x40, 0x48, 0x85, 0xc0, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+    0x48, 0x8b, 0x05, 0x10, 0x20, 0x30, 0x40, 0x38, 0x85, 0xc0, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+    0x4c, 0x8b, 0x25, 0x10, 0x20, 0x30, 0x40, 0x4d, 0x85, 0xc0, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+];
+
+const EXPECTED_FIND: usize = 0x30;
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn avx2() {
+    let scanner = Scanner::new(PATTERN);
+    let result = scanner.find_in_with_mode(Some(ScanMode::Avx2), &DATA_SET);
+
+    assert_eq!(result.unwrap().offset(), EXPECTED_FIND);
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn sse42() {
+    let scanner = Scanner::new(PATTERN);
+    let result = scanner.find_in_with_mode(Some(ScanMode::Sse42), &DATA_SET);
+
+    assert_eq!(result.unwrap().offset(), EXPECTED_FIND);
+}
+
+#[test]
+fn scalar() {
+    let scanner = Scanner::new(PATTERN);
+    let result = scanner.find_in_with_mode(Some(ScanMode::Scalar), &DATA_SET);
+
+    assert_eq!(result.unwrap().offset(), EXPECTED_FIND);
+}
+
+#[test]
+fn invalid_nibble() {
+    let err = Pattern::parse("48 ?g").err().unwrap();
+
+    assert_eq!(err.kind(), PatternErrorKind::InvalidHexDigit);
+    assert_eq!(err.column(), 5);
+    assert_eq!(err.token(), "?g");
+}
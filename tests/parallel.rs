@@ -0,0 +1,103 @@
+#![cfg(feature = "parallel")]
+
+use lightningscanner::pattern::Pattern;
+use lightningscanner::{ScanMode, Scanner};
+use tinyrand::{Rand, Wyrand};
+
+const PATTERN: [u8; 40] = [
+    0x48, 0x89, 0x5c, 0x24, 0x08, 0x48, 0x89, 0x6c, 0x24, 0x10, 0x48, 0x89, 0x74, 0x24, 0x18, 0x57,
+    0x41, 0x56, 0x41, 0x57, 0x48, 0x83, 0xec, 0x20, 0x4c, 0x8b, 0xf9, 0x48, 0x8b, 0x0d, 0x11, 0x22,
+    0x33, 0x44, 0x8b, 0x05, 0x55, 0x66, 0x77, 0x88,
+];
+
+const MIB: usize = 1024 * 1024;
+
+/// Random data with copies of the pattern straddling every MiB boundary and at the very end
+fn haystack() -> Vec<u8> {
+    let mut rand = Wyrand::default();
+
+    let mut data = (0..24 * MIB)
+        .map(|_| rand.next_u16() as u8)
+        .collect::<Vec<_>>();
+
+    for boundary in (MIB..data.len()).step_by(MIB) {
+        let start = boundary - 1 - rand.next_usize() % (PATTERN.len() - 1);
+        data[start..start + PATTERN.len()].copy_from_slice(&PATTERN);
+    }
+
+    let len = data.len();
+    data[len - PATTERN.len()..].copy_from_slice(&PATTERN);
+
+    data
+}
+
+fn same_as_sequential(scan_mode: ScanMode) {
+    let data = haystack();
+    let scanner = Scanner::from(Pattern::from_bytes(&PATTERN));
+
+    let sequential = scanner
+        .find_all_in_with_mode(Some(scan_mode), &data)
+        .collect::<Vec<_>>();
+    let parallel = scanner.par_find_all(Some(scan_mode), &data);
+
+    assert_eq!(sequential.len(), 24);
+    assert_eq!(parallel, sequential);
+
+    assert_eq!(
+        scanner.par_find(Some(scan_mode), &data),
+        scanner.find_in_with_mode(Some(scan_mode), &data)
+    );
+}
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn avx2() {
+    same_as_sequential(ScanMode::Avx2);
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn sse42() {
+    same_as_sequential(ScanMode::Sse42);
+}
+
+#[test]
+fn scalar() {
+    same_as_sequential(ScanMode::Scalar);
+}
+
+#[test]
+fn only_at_end() {
+    let mut data = vec![0u8; 20 * MIB];
+    let len = data.len();
+    data[len - PATTERN.len()..].copy_from_slice(&PATTERN);
+
+    let scanner = Scanner::from(Pattern::from_bytes(&PATTERN));
+    let result = scanner.par_find(None, &data).unwrap();
+
+    assert_eq!(result.offset(), len - PATTERN.len());
+    assert_eq!(result.haystack().len(), len);
+}
+
+#[test]
+fn result_offset() {
+    let data = haystack();
+    let scanner = Scanner::from(Pattern::from_bytes(&PATTERN).with_result_offset(30));
+
+    let offsets = scanner
+        .par_find_all(None, &data)
+        .iter()
+        .map(|result| result.offset() - result.start())
+        .collect::<Vec<_>>();
+
+    assert_eq!(offsets, [30; 24]);
+}
+
+#[test]
+fn no_match() {
+    let data = vec![0u8; 10 * MIB];
+    let scanner = Scanner::from(Pattern::from_bytes(&PATTERN));
+
+    assert!(scanner.par_find(None, &data).is_none());
+    assert!(scanner.par_find_all(None, &data).is_empty());
+}
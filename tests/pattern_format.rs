@@ -0,0 +1,56 @@
+use lightningscanner::pattern::Pattern;
+use std::collections::HashSet;
+
+const PATTERN: &str = "48 8B ? 4? ?5 E8";
+
+#[test]
+fn display_round_trip() {
+    let pattern = Pattern::new(PATTERN);
+
+    assert_eq!(pattern.to_string(), PATTERN);
+    assert_eq!(Pattern::new(&pattern.to_string()), pattern);
+}
+
+#[test]
+fn debug() {
+    let pattern = Pattern::new("48 8b ?? 4?");
+
+    assert_eq!(
+        format!("{pattern:?}"),
+        "Pattern { data: [48, 8b, 00, 40], mask: [ff, ff, 00, f0], unpadded_size: 4, result_offset: 0 }"
+    );
+}
+
+#[test]
+fn dialects() {
+    let pattern = Pattern::new(PATTERN);
+
+    assert_eq!(pattern.to_x64dbg_string(), "488B??4??5E8");
+    assert_eq!(pattern.to_cheat_engine_string(), "48 8B * 4? ?5 E8");
+
+    assert_eq!(Pattern::new(&pattern.to_x64dbg_string()), pattern);
+}
+
+#[test]
+fn partial_bit_mask() {
+    let pattern = Pattern::from_bytes_and_mask(&[0x48, 0xc0], &[0xf8, 0xc0]);
+
+    assert_eq!(pattern.to_string(), "4? ??");
+}
+
+#[test]
+fn clone_eq_hash() {
+    let pattern = Pattern::new(PATTERN);
+    let clone = pattern.clone();
+
+    assert_eq!(pattern, clone);
+    assert_ne!(pattern, Pattern::new("48 8B ? 4? ?5"));
+    // data bits outside of the mask don't affect equality
+    assert_eq!(
+        Pattern::new("48 ??"),
+        Pattern::from_bytes_and_mask(&[0x48, 0xcc], &[0xff, 0x00])
+    );
+
+    let set = [pattern, clone].into_iter().collect::<HashSet<_>>();
+    assert_eq!(set.len(), 1);
+}
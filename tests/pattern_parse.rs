@@ -0,0 +1,97 @@
+use lightningscanner::pattern::{Pattern, PatternErrorKind};
+use lightningscanner::Scanner;
+
+#[test]
+fn valid() {
+    let pattern = Pattern::parse("48 89 5C 24 ? 48 89 6c ??").unwrap();
+    let scanner = Scanner::from(pattern);
+
+    let data_set = [0x48, 0x89, 0x5c, 0x24, 0x10, 0x48, 0x89, 0x6c, 0x24];
+    assert_eq!(scanner.find_in(&data_set).unwrap().offset(), 0);
+}
+
+#[test]
+fn unspaced() {
+    let pattern = "48895c24??".parse::<Pattern>().unwrap();
+    let scanner = Scanner::from(pattern);
+
+    let data_set = [0xcc, 0x48, 0x89, 0x5c, 0x24, 0x10];
+    assert_eq!(scanner.find_in(&data_set).unwrap().offset(), 1);
+}
+
+#[test]
+fn invalid_hex_digit() {
+    let err = "48 89 4G 24".parse::<Pattern>().err().unwrap();
+
+    assert_eq!(err.kind(), PatternErrorKind::InvalidHexDigit);
+    assert_eq!(err.column(), 8);
+    assert_eq!(err.token(), "4G");
+
+    let err = Pattern::parse("48 zz").err().unwrap();
+
+    assert_eq!(err.kind(), PatternErrorKind::InvalidHexDigit);
+    assert_eq!(err.column(), 4);
+    assert_eq!(err.token(), "zz");
+}
+
+#[test]
+fn dangling_nibble() {
+    let err = Pattern::parse("48 89 5").err().unwrap();
+
+    assert_eq!(err.kind(), PatternErrorKind::DanglingNibble);
+    assert_eq!(err.column(), 7);
+    assert_eq!(err.token(), "5");
+
+    let err = Pattern::parse("48 8 5c").err().unwrap();
+
+    assert_eq!(err.kind(), PatternErrorKind::DanglingNibble);
+    assert_eq!(err.column(), 4);
+    assert_eq!(err.token(), "8");
+}
+
+#[test]
+fn empty_pattern() {
+    assert_eq!(
+        Pattern::parse("").err().unwrap().kind(),
+        PatternErrorKind::EmptyPattern
+    );
+    assert_eq!(
+        Pattern::parse("   ").err().unwrap().kind(),
+        PatternErrorKind::EmptyPattern
+    );
+}
+
+#[test]
+fn unsupported_token() {
+    let err = Pattern::parse("48 89 $5 24").err().unwrap();
+
+    assert_eq!(err.kind(), PatternErrorKind::UnsupportedToken);
+    assert_eq!(err.column(), 7);
+    assert_eq!(err.token(), "$5");
+    assert_eq!(err.to_string(), "unsupported token at column 7 (`$5`)");
+}
+
+#[test]
+#[should_panic(expected = "invalid hex digit")]
+fn new_panics() {
+    Pattern::new("48 4G");
+}
+
+#[test]
+fn from_str_slice() {
+    let scanner = Scanner::from(Pattern::from("48 89 5c"));
+    let pattern: Pattern = "48 89 5c".into();
+
+    let data_set = [0xcc, 0x48, 0x89, 0x5c];
+    assert_eq!(scanner.find_in(&data_set).unwrap().offset(), 1);
+    assert_eq!(
+        Scanner::from(pattern).find_in(&data_set).unwrap().offset(),
+        1
+    );
+}
+
+#[test]
+#[should_panic(expected = "invalid hex digit")]
+fn from_panics() {
+    let _ = Pattern::from("48 4G");
+}
@@ -0,0 +1,126 @@
+use lightningscanner::pe::{Pe, PeError};
+use lightningscanner::Scanner;
+
+/// PE32+ image with base 0x140000000 and the sections
+/// `.text` (RVA 0x1000, file offset 0x400), `.rdata` (RVA 0x2000, file offset 0x600)
+/// and `.data` (RVA 0x3000, file offset 0x800)
+///
+/// `.text` contains `sub rsp, 28h; lea rcx, [rip + 1005h]; call 0x1017; add rsp, 28h; xor eax, eax; ret`,
+/// the start of `.rdata` contains a copy of the first 16 bytes of `.text` followed by "Hello, world".
+const SAMPLE_64: &[u8] = include_bytes!("data/sample64.exe");
+
+/// PE32 image with base 0x400000 and the same sections as [`SAMPLE_64`]
+const SAMPLE_32: &[u8] = include_bytes!("data/sample32.exe");
+
+fn parse_and_scan(data: &[u8], is_64: bool, image_base: u64) {
+    let pe = Pe::parse(data).unwrap();
+
+    assert_eq!(pe.is_64(), is_64);
+    assert_eq!(pe.image_base(), image_base);
+    assert_eq!(pe.entry_point(), 0x1000);
+
+    let names = pe
+        .sections()
+        .iter()
+        .map(|section| section.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, [".text", ".rdata", ".data"]);
+
+    let text = pe.section(".text").unwrap();
+    assert!(text.is_executable() && text.is_readable() && !text.is_writable());
+    assert_eq!(pe.section_data(text).len(), 0x1e);
+    assert!(pe.section(".data").unwrap().is_writable());
+
+    let scanner = Scanner::new("48 83 ec 28 48 8d 0d ?? ?? ?? ??");
+
+    let result = pe.find_in_section(&scanner, ".text").unwrap();
+    assert_eq!(result.offset(), 0x400);
+    assert_eq!(pe.offset_to_rva(result.offset()), Some(0x1000));
+    assert_eq!(pe.offset_to_va(result.offset()), Some(image_base + 0x1000));
+
+    let result = pe.find_in_section(&scanner, ".rdata").unwrap();
+    assert_eq!(result.offset(), 0x600);
+    assert_eq!(pe.offset_to_rva(result.offset()), Some(0x2000));
+
+    // the copy in .rdata is not part of an executable section
+    let results = pe.find_all_in_executable_sections(&scanner);
+    assert_eq!(results.len(), 1);
+    assert_eq!(pe.find_in_executable_sections(&scanner), Some(results[0]));
+
+    // lea rcx, [rip + disp32] points at the string in .rdata,
+    // relative addressing spans sections, so it is resolved in RVA space
+    let lea = pe.find_in_section(&scanner, ".text").unwrap();
+    let disp = i32::from_le_bytes(lea.as_bytes()[7..11].try_into().unwrap());
+    let string = pe.offset_to_rva(lea.offset()).unwrap() + 11 + disp as u32;
+    assert_eq!(string, 0x2010);
+    assert_eq!(
+        pe.rva_to_offset(string)
+            .map(|offset| &data[offset..offset + 12]),
+        Some(&b"Hello, world"[..])
+    );
+
+    assert_eq!(pe.va_to_rva(image_base + 0x3000), Some(0x3000));
+    assert_eq!(pe.va_to_rva(image_base - 1), None);
+    assert_eq!(pe.rva_to_va(0x3000), image_base + 0x3000);
+
+    // .data is larger in memory than in the file
+    assert_eq!(pe.rva_to_offset(0x3100), Some(0x900));
+    assert_eq!(pe.rva_to_offset(0x3300), None);
+    assert_eq!(pe.offset_to_rva(0x300), None);
+
+    assert!(pe.find_in_section(&scanner, ".reloc").is_none());
+    assert!(pe.find_all_in_section(&scanner, ".data").is_empty());
+}
+
+#[test]
+fn pe32_plus() {
+    parse_and_scan(SAMPLE_64, true, 0x140000000);
+}
+
+#[test]
+fn pe32() {
+    parse_and_scan(SAMPLE_32, false, 0x400000);
+}
+
+#[test]
+fn errors() {
+    assert_eq!(Pe::parse(b"\x7fELF").unwrap_err(), PeError::BadDosMagic);
+    assert_eq!(
+        Pe::parse(&SAMPLE_64[..0x40]).unwrap_err(),
+        PeError::Truncated
+    );
+    assert_eq!(
+        Pe::parse(&SAMPLE_64[..0x200]).unwrap_err(),
+        PeError::Truncated
+    );
+
+    let mut data = SAMPLE_64.to_vec();
+    data[0x80] = b'N';
+    assert_eq!(Pe::parse(&data).unwrap_err(), PeError::BadNtSignature);
+
+    let mut data = SAMPLE_64.to_vec();
+    data[0x98] = 0x07;
+    data[0x99] = 0x01;
+    assert_eq!(
+        Pe::parse(&data).unwrap_err(),
+        PeError::UnsupportedOptionalHeader(0x107)
+    );
+}
+
+#[test]
+fn malformed_section_table() {
+    // .text at RVA 0xfffffff0, its last 14 bytes would lie past the 32-bit address space
+    let mut data = SAMPLE_64.to_vec();
+    data[0x194..0x198].copy_from_slice(&0xffff_fff0u32.to_le_bytes());
+
+    let pe = Pe::parse(&data).unwrap();
+    assert_eq!(pe.offset_to_rva(0x40f), Some(0xffff_ffff));
+    assert_eq!(pe.offset_to_rva(0x410), None);
+    assert_eq!(pe.offset_to_va(0x41d), None);
+    assert_eq!(pe.rva_to_offset(0xffff_ffff), Some(0x40f));
+
+    // .data with its file data at the end of the 32-bit offset range
+    let mut data = SAMPLE_64.to_vec();
+    data[0x1ec..0x1f0].copy_from_slice(&0xffff_ffffu32.to_le_bytes());
+    assert_eq!(Pe::parse(&data).unwrap_err(), PeError::Truncated);
+}
@@ -0,0 +1,170 @@
+#![cfg(target_os = "linux")]
+
+use lightningscanner::pattern::Pattern;
+use lightningscanner::process::Process;
+use lightningscanner::{MultiScanner, Scanner};
+use std::process::{Child, Command, Stdio};
+
+const MARKER: &str = "LIGHTNINGSCANNER_MARKER=5f3a9c1e7b2d4086";
+
+/// Child process with a known byte sequence in its environment, killed on drop
+struct ChildProcess(Child);
+
+impl ChildProcess {
+    fn spawn() -> Self {
+        let (key, value) = MARKER.split_once('=').unwrap();
+
+        let child = Command::new("sleep")
+            .arg("30")
+            .env(key, value)
+            .stdin(Stdio::null())
+            .spawn()
+            .unwrap();
+
+        ChildProcess(child)
+    }
+
+    fn process(&self) -> Process {
+        Process::new(self.0.id())
+    }
+}
+
+impl Drop for ChildProcess {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+/// Wait until the child has executed `sleep`, so its stack holds the environment
+fn wait_for_exec(process: &Process) {
+    for _ in 0..100 {
+        let exe = std::fs::read_link(format!("/proc/{}/exe", process.pid())).unwrap();
+        if exe.file_name().is_some_and(|name| name == "sleep") {
+            return;
+        }
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+    }
+
+    panic!("child process did not start");
+}
+
+#[test]
+fn regions() {
+    let regions = Process::current().regions().unwrap();
+
+    let stack = regions
+        .iter()
+        .find(|region| region.name() == "[stack]")
+        .unwrap();
+    assert!(stack.is_readable() && stack.is_writable() && !stack.is_executable());
+    assert!(stack.path().is_none());
+
+    let exe = std::env::current_exe().unwrap();
+    assert!(regions
+        .iter()
+        .any(|region| region.is_executable() && region.path() == Some(exe.as_path())));
+
+    assert!(regions
+        .windows(2)
+        .all(|pair| pair[0].end() <= pair[1].start()));
+}
+
+#[test]
+fn child_process() {
+    let child = ChildProcess::spawn();
+    let process = child.process();
+    wait_for_exec(&process);
+
+    let scanner = Scanner::from(Pattern::new_string(MARKER));
+    let address = process
+        .find(&scanner, |region| region.name() == "[stack]")
+        .unwrap()
+        .unwrap();
+
+    let mut buffer = [0; MARKER.len()];
+    assert_eq!(process.read(address, &mut buffer).unwrap(), MARKER.len());
+    assert_eq!(&buffer, MARKER.as_bytes());
+
+    let addresses = process.find_all(&scanner, |_| true).unwrap();
+    assert!(addresses.contains(&address));
+
+    let multi_scanner = [
+        Pattern::new_string(MARKER),
+        Pattern::new_string("LIGHTNINGSCANNER_MARKER="),
+        Pattern::new_string("LIGHTNINGSCANNER_NOT_PRESENT"),
+    ]
+    .into_iter()
+    .collect::<MultiScanner>();
+
+    let results = process
+        .find_multi(&multi_scanner, |region| region.name() == "[stack]")
+        .unwrap();
+    assert_eq!(results, [Some(address), Some(address), None]);
+}
+
+#[test]
+fn result_offset() {
+    let child = ChildProcess::spawn();
+    let process = child.process();
+    wait_for_exec(&process);
+
+    let scanner = Scanner::from(Pattern::new_string(MARKER));
+    let start = process.find(&scanner, |_| true).unwrap().unwrap();
+
+    let scanner = Scanner::from(Pattern::new_string(MARKER).with_result_offset(24));
+    let value = process.find(&scanner, |_| true).unwrap().unwrap();
+
+    assert_eq!(value, start + 24);
+}
+
+#[test]
+fn missing_process() {
+    let process = Process::new(u32::MAX);
+
+    assert!(process.regions().is_err());
+    assert!(process.find(&Scanner::new("48 8b"), |_| true).is_err());
+}
+
+/// Set the real, effective and saved user id of the calling thread only
+///
+/// The libc wrappers change the ids of every thread of the process, the system call does not.
+fn set_thread_uids(real: u32, effective: u32, saved: u32) {
+    // SAFETY: setresuid takes three integer arguments and has no memory side effects
+    let result = unsafe { libc::syscall(libc::SYS_setresuid, real, effective, saved) };
+    assert_eq!(result, 0, "{}", std::io::Error::last_os_error());
+}
+
+#[test]
+fn access_denied() {
+    // dropping privileges after the memory map was read needs root
+    // SAFETY: geteuid has no preconditions
+    if unsafe { libc::geteuid() } != 0 {
+        return;
+    }
+
+    let child = ChildProcess::spawn();
+    let process = child.process();
+    wait_for_exec(&process);
+
+    let scanner = Scanner::from(Pattern::new_string(MARKER));
+
+    // the saved user id stays root, so privileges can be restored afterwards
+    let mut dropped = false;
+    let result = process.find(&scanner, |_| {
+        if !dropped {
+            set_thread_uids(65534, 65534, 0);
+            dropped = true;
+        }
+
+        true
+    });
+    set_thread_uids(0, 0, 0);
+
+    let err = result.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+
+    // the marker is found again once the process can be read
+    assert!(process.find(&scanner, |_| true).unwrap().is_some());
+}
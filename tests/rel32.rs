@@ -0,0 +1,97 @@
+use lightningscanner::{Match, Scanner};
+
+#[rustfmt::skip]
+const DATA_SET: [u8; 48] = [
+    0xe8, 0x1b, 0x00, 0x00, 0x00,               // 0x00: call +0x1b
+    0x74, 0x04,                                 // 0x05: jz +0x04
+    0x0f, 0x85, 0xf7, 0xff, 0xff, 0xff,         // 0x07: jnz -0x09
+    0x48, 0x8b, 0x05, 0x10, 0x00, 0x00, 0x00,   // 0x0d: mov rax, [rip + 0x10]
+    0xff, 0x15, 0x00, 0x01, 0x00, 0x00,         // 0x14: call qword ptr [rip + 0x100]
+    0xe9, 0xe4, 0xff, 0xff, 0xff,               // 0x1a: jmp -0x1c
+    0x48, 0x8d, 0x0d, 0x06, 0x00, 0x00, 0x00,   // 0x1f: lea rcx, [rip + 0x6]
+    0x0f, 0xb6, 0x05, 0xe0, 0xff, 0xff, 0xff,   // 0x26: movzx eax, byte ptr [rip - 0x20]
+    0xcc, 0xcc, 0xc3,                           // 0x2d: int3; int3; ret
+];
+
+fn find(pattern: &str) -> Match<'static> {
+    Scanner::new(pattern).find_in(&DATA_SET).unwrap()
+}
+
+#[test]
+fn call() {
+    let result = find("e8 ?? ?? ?? ?? 74");
+
+    assert_eq!(result.resolve_call(), Some(0x20));
+    assert_eq!(result.resolve_rel32(1, 5), Some(0x20));
+    assert_eq!(result.resolve_jmp(), None);
+}
+
+#[test]
+fn jmp() {
+    assert_eq!(find("74 ?? 0f 85").resolve_jmp(), Some(0x0b));
+    assert_eq!(find("0f 85 ?? ?? ?? ?? 48").resolve_jmp(), Some(0x04));
+    assert_eq!(find("e9 ?? ?? ?? ?? 48 8d").resolve_jmp(), Some(0x03));
+}
+
+#[test]
+fn rip_relative() {
+    assert_eq!(find("48 8b 05").resolve_rip_relative(), Some(0x24));
+    assert_eq!(find("48 8d 0d").resolve_rip_relative(), Some(0x2c));
+    assert_eq!(find("0f b6 05").resolve_rip_relative(), Some(0x0d));
+    assert_eq!(find("e8 ?? ?? ?? ?? 74").resolve_rip_relative(), None);
+}
+
+#[test]
+fn out_of_bounds() {
+    // the call qword ptr target lies outside of the data set
+    assert_eq!(find("ff 15").resolve_rip_relative(), None);
+    // the displacement runs past the end of the data set
+    assert_eq!(find("cc cc c3").resolve_rel32(1, 5), None);
+}
+
+#[test]
+fn result_offset() {
+    let result = find("74 ?? & 0f 85");
+
+    assert_eq!(result.resolve_jmp(), Some(0x04));
+    assert_eq!(result.resolve_rel32(2, 6), Some(0x04));
+}
+
+#[test]
+fn raw_pointers() {
+    let data = DATA_SET;
+    let find = |pattern: &str| {
+        // SAFETY: data is a valid slice
+        unsafe { Scanner::new(pattern).find(None, data.as_ptr(), data.len()) }
+    };
+
+    let result = find("e8 ?? ?? ?? ?? 74");
+    assert_eq!(result.resolve_call(&data).get_addr(), data[0x20..].as_ptr());
+    assert_eq!(
+        result.resolve_rel32(&data, 1, 5),
+        result.resolve_call(&data)
+    );
+    assert!(!result.resolve_jmp(&data).is_valid());
+
+    let result = find("48 8d 0d");
+    assert_eq!(
+        result.resolve_rip_relative(&data).get_addr(),
+        data[0x2c..].as_ptr()
+    );
+
+    // targets past the end of the region
+    assert!(!find("ff 15").resolve_rip_relative(&data).is_valid());
+    assert!(!find("cc cc c3").resolve_rel32(&data, 1, 5).is_valid());
+
+    // instructions crossing the end of the region, or outside of it
+    let result = find("0f b6 05");
+    assert!(result.resolve_rip_relative(&data).is_valid());
+    assert!(!result.resolve_rip_relative(&data[..0x2a]).is_valid());
+    assert!(!result.resolve_rip_relative(&data[..0x20]).is_valid());
+    assert!(!result.resolve_rip_relative(&data[0x27..]).is_valid());
+    assert!(!result.resolve_rip_relative(&DATA_SET).is_valid());
+
+    let invalid = find("de ad");
+    assert!(!invalid.is_valid());
+    assert!(!invalid.resolve_call(&data).is_valid());
+}
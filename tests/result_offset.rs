@@ -0,0 +1,99 @@
+use lightningscanner::pattern::{Pattern, PatternErrorKind};
+use lightningscanner::{ScanMode, Scanner};
+
+// mov rax, [rip + disp32]; test rax, rax
+const PATTERN: &str = "48 8b 05 & ?? ?? ?? ?? 48 85 c0";
+
+const DATA_SET: [u8; 48] = [
+    0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+    0xcc, 0xcc, 0xcc, 0xcc, 0x48, 0x8b, 0x05, 0x10, 0x20, 0x30, 0x00, 0x48, 0x85, 0xc0, 0x74, 0x05,
+    0x48, 0x8b, 0x05, 0x11, 0x21, 0x31, 0x00, 0x48, 0x85, 0xc0, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc, 0xcc,
+];
+
+const EXPECTED_FIND: usize = 0x17;
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn avx2() {
+    let scanner = Scanner::new(PATTERN);
+    // SAFETY: DATA_SET is a valid slice
+    let result = unsafe { scanner.find(Some(ScanMode::Avx2), DATA_SET.as_ptr(), DATA_SET.len()) };
+
+    let data_set_addr = DATA_SET.as_ptr() as usize;
+    let ptr = result.get_addr() as usize;
+
+    assert_eq!(ptr - data_set_addr, EXPECTED_FIND);
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn sse42() {
+    let scanner = Scanner::new(PATTERN);
+    // SAFETY: DATA_SET is a valid slice
+    let result = unsafe { scanner.find(Some(ScanMode::Sse42), DATA_SET.as_ptr(), DATA_SET.len()) };
+
+    let data_set_addr = DATA_SET.as_ptr() as usize;
+    let ptr = result.get_addr() as usize;
+
+    assert_eq!(ptr - data_set_addr, EXPECTED_FIND);
+}
+
+#[test]
+fn scalar() {
+    let scanner = Scanner::new(PATTERN);
+    // SAFETY: DATA_SET is a valid slice
+    let result = unsafe { scanner.find(Some(ScanMode::Scalar), DATA_SET.as_ptr(), DATA_SET.len()) };
+
+    let data_set_addr = DATA_SET.as_ptr() as usize;
+    let ptr = result.get_addr() as usize;
+
+    assert_eq!(ptr - data_set_addr, EXPECTED_FIND);
+}
+
+#[test]
+fn safe_api() {
+    let scanner = Scanner::new(PATTERN);
+    let result = scanner.find_in(&DATA_SET).unwrap();
+
+    assert_eq!(result.offset(), EXPECTED_FIND);
+    assert_eq!(result.start(), 0x14);
+    assert_eq!(result.range(), 0x14..0x1e);
+}
+
+#[test]
+fn find_all() {
+    let scanner = Scanner::new(PATTERN);
+
+    let offsets = scanner
+        .find_all_in(&DATA_SET)
+        .map(|result| result.offset())
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, [0x17, 0x23]);
+
+    let data_set_addr = DATA_SET.as_ptr() as usize;
+    // SAFETY: DATA_SET is a valid slice
+    let offsets = unsafe { scanner.find_all(None, DATA_SET.as_ptr(), DATA_SET.len()) }
+        .map(|result| result.get_addr() as usize - data_set_addr)
+        .collect::<Vec<_>>();
+    assert_eq!(offsets, [0x17, 0x23]);
+}
+
+#[test]
+fn display_round_trip() {
+    let pattern = Pattern::new(PATTERN);
+
+    assert_eq!(pattern.to_string(), "48 8B 05 & ? ? ? ? 48 85 C0");
+    assert_eq!(Pattern::new(&pattern.to_string()), pattern);
+
+    let pattern = Pattern::new("e8 ?? ?? ?? ??&");
+    assert_eq!(pattern.result_offset(), 5);
+    assert_eq!(pattern.to_string(), "E8 ? ? ? ? &");
+}
+
+#[test]
+fn duplicate_marker() {
+    let err = Pattern::parse("48 & 8b & 05").err().unwrap();
+
+    assert_eq!(err.kind(), PatternErrorKind::DuplicateResultMarker);
+    assert_eq!(err.column(), 9);
+}
@@ -0,0 +1,79 @@
+use lightningscanner::{ScanMode, Scanner};
+
+const PATTERN: &str = "a0 9e 87 00 ?? 5c";
+
+const DATA_SET: [u8; 64] = [
+    0xdb, 0x2f, 0x16, 0x37, 0xd5, 0xff, 0x12, 0x74, 0x7c, 0xf2, 0x27, 0xed, 0x7b, 0x2e, 0x54, 0x9a,
+    0xe2, 0xec, 0x73, 0x9e, 0xbb, 0xd1, 0x42, 0xc2, 0x0c, 0x9e, 0xa3, 0xa1, 0x10, 0xb3, 0x97, 0xf2,
+    0xaf, 0x47, 0x43, 0x9f, 0xa0, 0x9e, 0x87, 0x00, 0x76, 0x5c, 0x3a, 0xae, 0x40, 0x30, 0x7f, 0xc0,
+    0x53, 0xf4, 0xeb, 0xcc, 0xf2, 0x04, 0x6d, 0x35, 0x5c, 0x88, 0xc3, 0x83, 0xdf, 0x9b, 0xa0, 0x9e,
+];
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn avx2() {
+    let scanner = Scanner::new(PATTERN);
+    let result = scanner.find_in_with_mode(Some(ScanMode::Avx2), &DATA_SET);
+
+    let result = result.unwrap();
+    assert_eq!(result.offset(), 0x24);
+    assert_eq!(result.as_bytes(), &DATA_SET[0x24..0x2a]);
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn sse42() {
+    let scanner = Scanner::new(PATTERN);
+    let result = scanner.find_in_with_mode(Some(ScanMode::Sse42), &DATA_SET);
+
+    let result = result.unwrap();
+    assert_eq!(result.offset(), 0x24);
+    assert_eq!(result.as_bytes(), &DATA_SET[0x24..0x2a]);
+}
+
+#[test]
+fn scalar() {
+    let scanner = Scanner::new(PATTERN);
+    let result = scanner.find_in_with_mode(Some(ScanMode::Scalar), &DATA_SET);
+
+    let result = result.unwrap();
+    assert_eq!(result.offset(), 0x24);
+    assert_eq!(result.range(), 0x24..0x2a);
+    assert_eq!(result.as_bytes(), &DATA_SET[0x24..0x2a]);
+}
+
+#[test]
+fn partial_match_at_end() {
+    let scanner = Scanner::new(PATTERN);
+
+    assert_eq!(
+        scanner.find_in_with_mode(Some(ScanMode::Scalar), &DATA_SET[0x25..]),
+        None
+    );
+}
+
+#[test]
+fn haystack_shorter_than_pattern() {
+    let scanner = Scanner::new(PATTERN);
+
+    assert_eq!(scanner.find_in(&DATA_SET[0x24..0x28]), None);
+}
+
+#[test]
+fn debug_and_equality() {
+    let data = DATA_SET;
+    let scanner = Scanner::new(PATTERN);
+    let result = scanner.find_in(&data).unwrap();
+
+    // only the matched bytes are printed, not the whole haystack
+    assert_eq!(
+        format!("{result:x?}"),
+        "Match { offset: 24, range: 24..2a, bytes: [a0, 9e, 87, 0, 76, 5c] }"
+    );
+
+    assert_eq!(result, scanner.find_in(&data).unwrap());
+
+    // equal contents in a different haystack are a different match
+    let copy = data;
+    assert_ne!(result, scanner.find_in(&copy).unwrap());
+}
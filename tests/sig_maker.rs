@@ -0,0 +1,187 @@
+use lightningscanner::pattern::Pattern;
+use lightningscanner::sig_maker::{SigMaker, SigMakerError};
+use lightningscanner::Scanner;
+use tinyrand::{Rand, Wyrand};
+
+/// Function with a variety of instruction encodings, `VV` marks address-dependent bytes
+const FUNCTION: &str = "
+    48 89 5c 24 08                  // mov [rsp + 8], rbx
+    57                              // push rdi
+    48 83 ec 20                     // sub rsp, 20h
+    48 8b 05 VV VV VV VV            // mov rax, [rip + disp32]
+    48 8d 0d VV VV VV VV            // lea rcx, [rip + disp32]
+    e8 VV VV VV VV                  // call rel32
+    85 c0                           // test eax, eax
+    0f 84 VV VV VV VV               // jz rel32
+    c7 05 VV VV VV VV 01 00 00 00   // mov dword ptr [rip + disp32], 1
+    48 b8 VV VV VV VV VV VV VV VV   // mov rax, imm64
+    f6 c1 01                        // test cl, 1
+    f7 d8                           // neg eax
+    66 0f 1f 44 00 00               // nop word ptr [rax + rax]
+    c5 f8 77                        // vzeroupper
+    c4 e3 79 16 c0 01               // vpextrd eax, xmm0, 1
+    8b 04 8d VV VV VV VV            // mov eax, [rcx * 4 + disp32]
+    0f 38 00 c1                     // pshufb mm0, mm1
+    eb VV                           // jmp rel8
+    48 83 c4 20                     // add rsp, 20h
+    5f                              // pop rdi
+    c3                              // ret
+";
+
+/// Get the bytes of [`FUNCTION`], with `volatile` in place of the address-dependent bytes
+fn function(volatile: &str) -> String {
+    FUNCTION
+        .lines()
+        .map(|line| line.split("//").next().unwrap())
+        .collect::<Vec<_>>()
+        .join(" ")
+        .replace("VV", volatile)
+}
+
+fn function_bytes(volatile: &str) -> Vec<u8> {
+    function(volatile)
+        .split_whitespace()
+        .map(|byte| u8::from_str_radix(byte, 16).unwrap())
+        .collect()
+}
+
+fn matches_of(pattern: &Pattern, image: &[u8]) -> Vec<usize> {
+    Scanner::from(pattern.clone())
+        .find_all_in(image)
+        .map(|result| result.offset())
+        .collect()
+}
+
+#[test]
+fn wildcards_address_dependent_bytes() {
+    // two copies that only differ in address-dependent bytes and the byte following them
+    let mut image = vec![0xcc; 16];
+    let first = image.len();
+    image.extend(function_bytes("11"));
+    image.push(0xcc);
+    image.extend(function_bytes("22"));
+    image.push(0x90);
+
+    let pattern = SigMaker::new(&image).with_max_len(128).make(first).unwrap();
+
+    assert_eq!(pattern, Pattern::new(&format!("{} cc", function("??"))));
+    assert_eq!(matches_of(&pattern, &image), [first]);
+}
+
+#[test]
+fn rex_w_overrides_operand_size() {
+    // add rax, 44332211h with a redundant operand size override; mov rax, [rip + disp32]
+    let mut image = vec![0xcc; 16];
+    let first = image.len();
+    for (disp, next) in [(0x11, 0xc3), (0x22, 0x90)] {
+        image.extend([0x66, 0x48, 0x81, 0xc0, 0x11, 0x22, 0x33, 0x44]);
+        image.extend([0x48, 0x8b, 0x05, disp, disp, disp, disp, next]);
+    }
+
+    let pattern = SigMaker::new(&image).make(first).unwrap();
+
+    assert_eq!(
+        pattern,
+        Pattern::new("66 48 81 c0 11 22 33 44 48 8b 05 ?? ?? ?? ?? c3")
+    );
+    assert_eq!(matches_of(&pattern, &image), [first]);
+}
+
+#[test]
+fn shortest_unique() {
+    // mov eax, 1; ret; mov eax, 2; ret
+    let image = [
+        0xb8, 0x01, 0x00, 0x00, 0x00, 0xc3, 0xb8, 0x02, 0x00, 0x00, 0x00, 0xc3,
+    ];
+    let sig_maker = SigMaker::new(&image);
+
+    assert_eq!(sig_maker.make(0), Ok(Pattern::new("b8 01")));
+    assert_eq!(sig_maker.make(6), Ok(Pattern::new("b8 02")));
+    assert_eq!(sig_maker.make(5), Ok(Pattern::new("c3 b8")));
+    // the last ret is only unique because nothing follows it
+    assert_eq!(sig_maker.make(11), Err(SigMakerError::NotUnique));
+}
+
+#[test]
+fn anchored() {
+    // mov rax, [rip + disp32]; mov ecx, imm32; xor eax, eax; ret, padded with int3
+    let mut image = vec![0xcc; 16];
+    let mut targets = Vec::new();
+    for imm in [1, 2] {
+        image.extend([0x48, 0x8b, 0x05, imm, imm, imm, imm]);
+        image.extend([0xb9, imm, 0x00, 0x00, 0x00]);
+        targets.push(image.len());
+        image.extend([0x31, 0xc0, 0xc3]);
+        image.extend([0xcc; 16]);
+    }
+
+    let sig_maker = SigMaker::new(&image);
+
+    // the tails are equal, so the forward pattern has to reach into the next function
+    let forward = sig_maker.make(targets[0]).unwrap();
+    assert_eq!(matches_of(&forward, &image), [targets[0]]);
+    assert!(forward.to_string().ends_with("CC 48"));
+
+    for target in targets {
+        let pattern = sig_maker.make_anchored(target).unwrap();
+
+        assert!(pattern.result_offset() > 0);
+        assert!(pattern.to_string().len() < forward.to_string().len());
+        assert_eq!(matches_of(&pattern, &image), [target]);
+    }
+
+    assert_eq!(
+        sig_maker.with_max_anchor_distance(0).make_anchored(16),
+        Err(SigMakerError::NotUnique)
+    );
+}
+
+#[test]
+fn random_images() {
+    let mut rand = Wyrand::default();
+    let image = (0..16 * 1024)
+        .map(|_| rand.next_u16() as u8)
+        .collect::<Vec<_>>();
+    let sig_maker = SigMaker::new(&image);
+
+    for _ in 0..200 {
+        let offset = rand.next_usize() % image.len();
+
+        if let Ok(pattern) = sig_maker.make(offset) {
+            assert_eq!(matches_of(&pattern, &image), [offset]);
+            assert_eq!(pattern.result_offset(), 0);
+        }
+
+        if let Ok(pattern) = sig_maker.make_anchored(offset) {
+            assert_eq!(matches_of(&pattern, &image), [offset]);
+            assert!(pattern.result_offset() > 0);
+        }
+    }
+}
+
+#[test]
+fn errors() {
+    let image = [0x90; 256];
+    let sig_maker = SigMaker::new(&image);
+
+    assert_eq!(sig_maker.make(0), Err(SigMakerError::NotUnique));
+    assert_eq!(sig_maker.make_anchored(128), Err(SigMakerError::NotUnique));
+    assert_eq!(sig_maker.make(256), Err(SigMakerError::OutOfBounds));
+    assert_eq!(
+        sig_maker.make_anchored(256),
+        Err(SigMakerError::OutOfBounds)
+    );
+
+    // unique, but only after 6 bytes
+    let image = [
+        0x90, 0x90, 0x90, 0x90, 0x90, 0xc3, 0x90, 0x90, 0x90, 0x90, 0x90, 0x90,
+    ];
+    assert_eq!(
+        SigMaker::new(&image).with_max_len(5).make(0),
+        Err(SigMakerError::NotUnique)
+    );
+    assert_eq!(
+        SigMaker::new(&image).make(0),
+        Ok(Pattern::new("90 90 90 90 90 c3"))
+    );
+}
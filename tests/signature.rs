@@ -0,0 +1,198 @@
+#![cfg(feature = "serde")]
+
+use lightningscanner::pe::Pe;
+use lightningscanner::signature::{Image, ResolveError, SignatureError, SignatureSet, Step};
+
+/// PE32+ image with base 0x140000000, see `tests/pe.rs`
+const SAMPLE_64: &[u8] = include_bytes!("data/sample64.exe");
+
+const SIGNATURES_TOML: &str = r#"
+[[signature]]
+name = "HelloWorld"
+pattern = "48 83 ec 28 48 8d 0d ?? ?? ?? ??"
+section = ".text"
+steps = [{ op = "add", offset = 4 }, { op = "rel32", disp_offset = 3, instr_len = 7 }]
+
+[[signature]]
+name = "Main"
+pattern = "48 83 ec 28 & 48 8d 0d"
+result_offset = 0
+
+[[signature]]
+name = "Missing"
+pattern = "de ad be ef"
+
+[[signature]]
+name = "NoSection"
+pattern = "48 83 ec 28"
+section = ".reloc"
+"#;
+
+const SIGNATURES_JSON: &str = r#"
+{
+    "signatures": [
+        {
+            "name": "Pointer",
+            "pattern": "ca fe & ?? ?? ?? ??",
+            "steps": [{ "op": "rel32", "disp_offset": 0, "instr_len": 4 }, { "op": "deref" }]
+        },
+        {
+            "name": "OutOfBounds",
+            "pattern": "ca fe",
+            "steps": [{ "op": "deref" }]
+        }
+    ]
+}
+"#;
+
+#[test]
+fn load_toml() {
+    let signatures = SignatureSet::from_toml_str(SIGNATURES_TOML).unwrap();
+    assert_eq!(signatures.len(), 4);
+
+    let names = signatures
+        .signatures()
+        .iter()
+        .map(|signature| signature.name())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["HelloWorld", "Main", "Missing", "NoSection"]);
+
+    let hello = signatures.get("HelloWorld").unwrap();
+    assert_eq!(hello.section(), Some(".text"));
+    assert_eq!(hello.module(), None);
+    assert_eq!(
+        hello.steps(),
+        [
+            Step::Add { offset: 4 },
+            Step::Rel32 {
+                disp_offset: 3,
+                instr_len: 7
+            }
+        ]
+    );
+
+    // an explicit result offset overrides the marker
+    let main = signatures.get("Main").unwrap();
+    assert_eq!(main.pattern().result_offset(), 0);
+    assert!(main.steps().is_empty());
+}
+
+#[test]
+fn resolve_pe() {
+    let pe = Pe::parse(SAMPLE_64).unwrap();
+    let signatures = SignatureSet::from_toml_str(SIGNATURES_TOML).unwrap();
+
+    let resolution = signatures.resolve_all(&pe);
+    assert!(!resolution.is_complete());
+
+    // lea rcx, [rip + disp32] points at "Hello, world" in .rdata
+    assert_eq!(resolution.get("HelloWorld"), Some(0x140002010));
+    assert_eq!(resolution.get("Main"), Some(0x140001000));
+    assert_eq!(resolution.addresses().len(), 2);
+
+    assert_eq!(
+        resolution.failures(),
+        [
+            ("Missing".to_string(), ResolveError::NotFound),
+            (
+                "NoSection".to_string(),
+                ResolveError::SectionNotFound(".reloc".to_string())
+            ),
+        ]
+    );
+
+    let mut string = [0; 12];
+    pe.read(0x140002010, &mut string).unwrap();
+    assert_eq!(&string, b"Hello, world");
+}
+
+#[test]
+fn resolve_bytes() {
+    let signatures = SignatureSet::from_json_str(SIGNATURES_JSON).unwrap();
+
+    let mut binary = [0u8; 64];
+    binary[8..10].copy_from_slice(&[0xca, 0xfe]);
+    // displacement to offset 32 from the end of the displacement at offset 14
+    binary[10..14].copy_from_slice(&18i32.to_le_bytes());
+    binary[32..32 + std::mem::size_of::<usize>()].copy_from_slice(&0x1234usize.to_le_bytes());
+
+    let resolution = signatures.resolve_all(&binary[..]);
+    assert_eq!(resolution.get("Pointer"), Some(0x1234));
+
+    // the matched bytes themselves are read as a pointer
+    let out_of_bounds = signatures.get("OutOfBounds").unwrap();
+    assert_eq!(out_of_bounds.resolve(&binary[..]), Ok(0x12feca));
+
+    let truncated = &binary[..12];
+    assert_eq!(
+        out_of_bounds.resolve(truncated),
+        Err(ResolveError::Unmapped(8))
+    );
+}
+
+#[test]
+fn load_file() {
+    let dir =
+        std::env::temp_dir().join(format!("lightningscanner-signature-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let toml = dir.join("signatures.toml");
+    std::fs::write(&toml, SIGNATURES_TOML).unwrap();
+    assert_eq!(SignatureSet::load(&toml).unwrap().len(), 4);
+
+    let json = dir.join("signatures.JSON");
+    std::fs::write(&json, SIGNATURES_JSON).unwrap();
+    assert_eq!(SignatureSet::load(&json).unwrap().len(), 2);
+
+    let yaml = dir.join("signatures.yaml");
+    std::fs::write(&yaml, "").unwrap();
+    assert!(matches!(
+        SignatureSet::load(&yaml),
+        Err(SignatureError::UnknownFormat)
+    ));
+
+    assert!(matches!(
+        SignatureSet::load(dir.join("missing.toml")),
+        Err(SignatureError::Io(_))
+    ));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn invalid_files() {
+    let err = SignatureSet::from_toml_str("[[signature]]\nname = \"Bad\"\npattern = \"48 zz\"")
+        .unwrap_err();
+    match err {
+        SignatureError::Pattern { name, error } => {
+            assert_eq!(name, "Bad");
+            assert_eq!(error.token(), "zz");
+        }
+        err => panic!("unexpected error {err:?}"),
+    }
+
+    let err = SignatureSet::from_toml_str(
+        "[[signature]]\nname = \"A\"\npattern = \"90\"\n[[signature]]\nname = \"A\"\npattern = \"cc\"",
+    )
+    .unwrap_err();
+    assert!(matches!(err, SignatureError::DuplicateName(name) if name == "A"));
+
+    let err = SignatureSet::from_toml_str(
+        "[[signature]]\nname = \"A\"\npattern = \"90 90\"\nresult_offset = 3",
+    )
+    .unwrap_err();
+    assert!(matches!(err, SignatureError::ResultOffsetOutOfBounds(name) if name == "A"));
+
+    // typos are reported instead of silently ignored
+    let err =
+        SignatureSet::from_toml_str("[[signature]]\nname = \"A\"\npatern = \"90\"").unwrap_err();
+    assert!(matches!(err, SignatureError::Toml(_)));
+
+    let err = SignatureSet::from_json_str(
+        r#"{"signature": [{"name": "A", "pattern": "90", "steps": [{"op": "jump"}]}]}"#,
+    )
+    .unwrap_err();
+    assert!(matches!(err, SignatureError::Json(_)));
+
+    assert!(SignatureSet::from_toml_str("").unwrap().is_empty());
+}
@@ -0,0 +1,136 @@
+use lightningscanner::pattern::Pattern;
+use lightningscanner::{ScanMode, Scanner, StreamScanner};
+use std::io::{self, Read};
+use tinyrand::{Probability, Rand, Wyrand};
+
+const PATTERN: &str = "48 89 5c 24 ?? 48 89 6c 24 ?? 48 89 74 24 ?? 57 41 56 41 57 48 83 ec 20 4c 8b f9 48 8b ?? & ?? ?? 8b";
+
+const PATTERN_BYTES: [u8; 33] = [
+    0x48, 0x89, 0x5c, 0x24, 0x08, 0x48, 0x89, 0x6c, 0x24, 0x10, 0x48, 0x89, 0x74, 0x24, 0x18, 0x57,
+    0x41, 0x56, 0x41, 0x57, 0x48, 0x83, 0xec, 0x20, 0x4c, 0x8b, 0xf9, 0x48, 0x8b, 0x11, 0x22, 0x33,
+    0x8b,
+];
+
+/// Reader returning short reads of random length, interrupted every now and then
+struct ChoppyReader {
+    data: Vec<u8>,
+    position: usize,
+    rand: Wyrand,
+}
+
+impl Read for ChoppyReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.rand.next_bool(Probability::new(0.125)) {
+            return Err(io::ErrorKind::Interrupted.into());
+        }
+
+        let len = (1 + self.rand.next_usize() % 64)
+            .min(buf.len())
+            .min(self.data.len() - self.position);
+        buf[..len].copy_from_slice(&self.data[self.position..self.position + len]);
+        self.position += len;
+
+        Ok(len)
+    }
+}
+
+/// Reader failing after the first `0x100` bytes
+struct FailingReader(usize);
+
+impl Read for FailingReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0 >= 0x100 {
+            return Err(io::Error::other("device lost"));
+        }
+
+        let len = buf.len().min(0x100 - self.0);
+        buf[..len].fill(0);
+        self.0 += len;
+
+        Ok(len)
+    }
+}
+
+/// Random data with overlapping copies of the pattern, including one at the very end
+fn data_set() -> Vec<u8> {
+    let mut rand = Wyrand::default();
+
+    let mut data = (0..0x4000)
+        .map(|_| rand.next_u16() as u8)
+        .collect::<Vec<_>>();
+
+    for start in [0x00, 0x7f0, 0x812, 0x1000, 0x1fff, 0x3000] {
+        data[start..start + PATTERN_BYTES.len()].copy_from_slice(&PATTERN_BYTES);
+    }
+
+    let len = data.len();
+    data[len - PATTERN_BYTES.len()..].copy_from_slice(&PATTERN_BYTES);
+
+    data
+}
+
+fn same_as_in_memory(scan_mode: ScanMode) {
+    let data = data_set();
+    let scanner = Scanner::new(PATTERN);
+
+    let expected = scanner
+        .find_all_in_with_mode(Some(scan_mode), &data)
+        .map(|result| result.offset() as u64)
+        .collect::<Vec<_>>();
+    assert_eq!(expected.len(), 7);
+
+    for block_size in [1, 7, 32, 33, 34, 0x100, 0x10000] {
+        let offsets = StreamScanner::new(scanner.clone(), data.as_slice())
+            .with_block_size(block_size)
+            .with_scan_mode(scan_mode)
+            .collect::<io::Result<Vec<_>>>()
+            .unwrap();
+
+        assert_eq!(offsets, expected, "block size {block_size}");
+    }
+
+    let reader = ChoppyReader {
+        data: data.clone(),
+        position: 0,
+        rand: Wyrand::default(),
+    };
+    let offsets = StreamScanner::new(scanner, reader)
+        .with_block_size(0x80)
+        .with_scan_mode(scan_mode)
+        .collect::<io::Result<Vec<_>>>()
+        .unwrap();
+
+    assert_eq!(offsets, expected);
+}
+
+#[test]
+#[cfg(target_feature = "avx2")]
+fn avx2() {
+    same_as_in_memory(ScanMode::Avx2);
+}
+
+#[test]
+#[cfg(target_feature = "sse4.2")]
+fn sse42() {
+    same_as_in_memory(ScanMode::Sse42);
+}
+
+#[test]
+fn scalar() {
+    same_as_in_memory(ScanMode::Scalar);
+}
+
+#[test]
+fn read_error() {
+    let mut scanner = StreamScanner::new(Scanner::new("ff ff"), FailingReader(0));
+
+    let err = scanner.next().unwrap().unwrap_err();
+    assert_eq!(err.to_string(), "device lost");
+}
+
+#[test]
+fn empty_stream() {
+    let scanner = StreamScanner::new(Scanner::from(Pattern::new("48 8b")), io::empty());
+
+    assert_eq!(scanner.count(), 0);
+}